@@ -0,0 +1,369 @@
+use std::{fmt, mem};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    ismcts::{self, Budget},
+    knowledge::Knowledge,
+    player::{Observation, Player},
+    solver,
+    trick::{self, Trick},
+    Action, Card, CardsValues, GameEvent, GameState,
+};
+
+/// Points on the table for which it is worth spending a triunfo to win the trick
+const WORTH_A_TRIUNFO: u16 = 10;
+/// Deals of the unseen cards the search plays every card against
+const SEARCH_SAMPLES: usize = 30;
+/// What winning or losing the game is worth to the searches, more than any hand
+pub const GAME_WON_MARGIN: i32 = 200;
+
+/// How well the computer plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    //any legal card
+    Random,
+    //rules of thumb, see `heuristic_action`
+    #[default]
+    Heuristic,
+    //plays every card against random deals of the unseen cards and keeps the best one, both
+    //searches solve the arrastre of a two player game with `solver`
+    Search,
+    //a tree search over the plays of every seat, see `ismcts`
+    Expert(Budget),
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Random,
+        Difficulty::Heuristic,
+        Difficulty::Search,
+        Difficulty::Expert(Budget::DEFAULT),
+    ];
+
+    /// The level after this one, to cycle through them
+    pub fn next(&self) -> Difficulty {
+        let index = Difficulty::ALL
+            .iter()
+            .position(|difficulty| mem::discriminant(difficulty) == mem::discriminant(self))
+            .map_or(0, |index| (index + 1) % Difficulty::ALL.len());
+        Difficulty::ALL[index]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Random => "Random",
+            Difficulty::Heuristic => "Heuristic",
+            Difficulty::Search => "Search",
+            Difficulty::Expert(_) => "Expert",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Expert(budget) => write!(f, "{}, {}", self.name(), budget),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// The computer sitting at a seat, with random choices of its own
+#[derive(Debug, Clone)]
+pub struct Computer {
+    difficulty: Difficulty,
+    rng: StdRng,
+}
+
+impl Computer {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Computer {
+            difficulty,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+}
+
+impl Player for Computer {
+    fn act(&mut self, observation: &Observation) -> Option<Action> {
+        //the computer plays on a guess of the cards it can not see, the searches guess again
+        //on their own
+        let game = Knowledge::observe(observation).sample(observation, &mut self.rng);
+        choose_action(&game, observation.seat(), self.difficulty, &mut self.rng)
+    }
+}
+
+/// What a computer player in `seat` does now at `difficulty`, if anything
+pub fn choose_action<R: Rng>(
+    game: &GameState,
+    seat: usize,
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> Option<Action> {
+    match difficulty {
+        Difficulty::Random => random_action(game, seat, rng),
+        Difficulty::Heuristic => heuristic_action(game, seat),
+        Difficulty::Search | Difficulty::Expert(_) => match heuristic_action(game, seat)? {
+            Action::Play { .. } => Some(Action::Play {
+                seat,
+                card: searched_card(game, seat, difficulty, rng),
+            }),
+            action => Some(action),
+        },
+    }
+}
+
+fn searched_card<R: Rng>(
+    game: &GameState,
+    seat: usize,
+    difficulty: Difficulty,
+    rng: &mut R,
+) -> usize {
+    //between two players every card is known in the arrastre, and the hand can be solved
+    if let Some(solution) = solver::solve(game) {
+        return solution.card;
+    }
+    match difficulty {
+        Difficulty::Expert(budget) => ismcts::search(game, seat, budget, rng),
+        _ => search_card(game, seat, rng),
+    }
+}
+
+fn random_action<R: Rng>(game: &GameState, seat: usize, rng: &mut R) -> Option<Action> {
+    if game.phase().is_over() {
+        return None;
+    }
+    let mut actions = Vec::new();
+    if game.tute_figure(seat).is_some() {
+        actions.push(Action::SingTute { seat });
+    }
+    if should_exchange(game, seat) {
+        actions.push(Action::Exchange { seat });
+    }
+    if !game.available_cantes(seat).is_empty() {
+        actions.push(Action::Sing { seat });
+    }
+    if game.turn() == seat {
+        actions.extend(
+            game.playable_cards(seat)
+                .into_iter()
+                .map(|card| Action::Play { seat, card }),
+        );
+    }
+    actions.choose(rng).copied()
+}
+
+/// A player by rules of thumb. It sings tute and cantes as soon as it can, exchanges the
+/// face up card when it is better, and plays when it is its turn
+pub fn heuristic_action(game: &GameState, seat: usize) -> Option<Action> {
+    if game.phase().is_over() {
+        return None;
+    }
+    if game.tute_figure(seat).is_some() {
+        return Some(Action::SingTute { seat });
+    }
+    if should_exchange(game, seat) {
+        return Some(Action::Exchange { seat });
+    }
+    if !game.available_cantes(seat).is_empty() {
+        return Some(Action::Sing { seat });
+    }
+    if game.turn() == seat {
+        return Some(Action::Play {
+            seat,
+            card: choose_card(game, seat),
+        });
+    }
+    None
+}
+
+fn should_exchange(game: &GameState, seat: usize) -> bool {
+    //never for a worse card, which would also let the same cards be exchanged back and forth
+    let (Some(index), Some(triunfo_card)) =
+        (game.exchangeable_card(seat), game.talon().triunfo_card())
+    else {
+        return false;
+    };
+    triunfo_card.kill_power() > game.hand(seat)[index].kill_power()
+}
+
+/// The index in the hand of the card to play: the cheapest one when leading or losing the
+/// trick anyway, the points to the partner when it is winning, and a cheap card that wins
+/// the trick when there is one
+pub(crate) fn choose_card(game: &GameState, seat: usize) -> usize {
+    let hand = game.hand(seat);
+    let triunfo = game.triunfo();
+    let trick = game.trick();
+    let playable = game.playable_cards(seat);
+    let cost = |&index: &usize| card_cost(game, seat, &hand[index]);
+    let cheapest = |cards: &[usize]| {
+        *cards
+            .iter()
+            .min_by_key(|index| cost(index))
+            .expect("the seat whose turn it is has cards")
+    };
+
+    let Some((winner, winning)) = trick.winning(triunfo) else {
+        return cheapest(&playable);
+    };
+    let seats = game.seats();
+    if seats.side(winner) == seats.side(seat) {
+        //load the trick with points, keeping the triunfos and the cantes
+        return *playable
+            .iter()
+            .max_by_key(|&&index| {
+                let card = &hand[index];
+                (
+                    card.palo != triunfo,
+                    !keeps_cante(game, seat, card),
+                    card.value(),
+                )
+            })
+            .expect("the seat whose turn it is has cards");
+    }
+    let winning_cards = playable
+        .iter()
+        .copied()
+        .filter(|&index| {
+            let card = &hand[index];
+            trick::do_x_defeat_y(card, winning, triunfo)
+                && (card.palo != triunfo || worth_a_triunfo(trick))
+        })
+        .collect::<Vec<usize>>();
+    if winning_cards.is_empty() {
+        cheapest(&playable)
+    } else {
+        cheapest(&winning_cards)
+    }
+}
+
+fn worth_a_triunfo(trick: &Trick) -> bool {
+    trick.points() >= WORTH_A_TRIUNFO
+}
+
+fn card_cost(game: &GameState, seat: usize, card: &Card) -> (bool, bool, u8, u8) {
+    //triunfos are saved first, then the figures of a cante still to sing, then the points
+    (
+        card.palo == game.triunfo(),
+        keeps_cante(game, seat, card),
+        card.value(),
+        card.kill_power(),
+    )
+}
+
+fn keeps_cante(game: &GameState, seat: usize, card: &Card) -> bool {
+    //a Rey or Caballo whose pair is also in the hand and has not been sung
+    let pair = match card.value {
+        CardsValues::Rey => CardsValues::Caballo,
+        CardsValues::Caballo => CardsValues::Rey,
+        _ => return false,
+    };
+    let pair = Card {
+        value: pair,
+        palo: card.palo,
+    };
+    game.hand(seat).contains(&pair) && !game.revealed().contains(card)
+}
+
+/// The card that does best on average when the hand is played out by the heuristic against
+/// `SEARCH_SAMPLES` deals of the cards `seat` can not see
+fn search_card<R: Rng>(game: &GameState, seat: usize, rng: &mut R) -> usize {
+    let playable = game.playable_cards(seat);
+    if playable.len() == 1 {
+        return playable[0];
+    }
+    let side = game.seats().side(seat);
+    let observation = Observation::new(game, seat);
+    let knowledge = Knowledge::observe(&observation);
+    let mut totals = vec![0; playable.len()];
+    for _ in 0..SEARCH_SAMPLES {
+        //every card is played against the same deal so they are compared fairly
+        let deal = knowledge.sample(&observation, rng);
+        for (total, &card) in totals.iter_mut().zip(&playable) {
+            let mut game = deal.clone();
+            let events = game
+                .apply(Action::Play { seat, card })
+                .expect("a playable card can be played");
+            let margins = hand_margins(&game, &events).unwrap_or_else(|| play_out(&mut game));
+            *total += margins[side];
+        }
+    }
+    let best = (0..playable.len())
+        .max_by_key(|&i| totals[i])
+        .expect("the seat whose turn it is has cards");
+    playable[best]
+}
+
+/// Plays the rest of the hand with the heuristic for every seat, and returns by how many
+/// points each side won or lost it
+pub fn play_out(game: &mut GameState) -> Vec<i32> {
+    let players = game.seats().players();
+    while let Some(action) = (0..players).find_map(|seat| heuristic_action(game, seat)) {
+        let events = game
+            .apply(action)
+            .expect("the heuristic only takes legal actions");
+        if let Some(margins) = hand_margins(game, &events) {
+            return margins;
+        }
+    }
+    vec![0; game.seats().sides()]
+}
+
+/// Plays `game` with the heuristic for every seat until it is over, or until `visit`, called
+/// with the game and the action about to be taken, returns false
+#[cfg(test)]
+pub(crate) fn play_out_heuristic(
+    game: &mut GameState,
+    mut visit: impl FnMut(&GameState, Action) -> bool,
+) {
+    let players = game.seats().players();
+    while let Some(action) = (0..players).find_map(|seat| heuristic_action(game, seat)) {
+        if !visit(game, action) {
+            return;
+        }
+        game.apply(action)
+            .expect("the heuristic only takes legal actions");
+    }
+}
+
+/// By how many points each side won or lost the hand, if `events` ended it. Winning the game
+/// is worth `GAME_WON_MARGIN` more
+pub fn hand_margins(game: &GameState, events: &[GameEvent]) -> Option<Vec<i32>> {
+    let sides = game.seats().sides();
+    let mut margins = None;
+    for event in events {
+        match event {
+            GameEvent::HandScored { score, .. } => {
+                let totals = (0..sides)
+                    .map(|side| i32::from(score.total(side)))
+                    .collect::<Vec<i32>>();
+                let margins = margins.get_or_insert(vec![0; sides]);
+                for side in 0..sides {
+                    let best_other = (0..sides)
+                        .filter(|&other| other != side)
+                        .map(|other| totals[other])
+                        .max()
+                        .unwrap_or(0);
+                    margins[side] += totals[side] - best_other;
+                }
+            }
+            GameEvent::GameWon { side: winner } => {
+                let margins = margins.get_or_insert(vec![0; sides]);
+                for (side, margin) in margins.iter_mut().enumerate() {
+                    *margin += if side == *winner {
+                        GAME_WON_MARGIN
+                    } else {
+                        -GAME_WON_MARGIN
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+    margins
+}
@@ -0,0 +1,49 @@
+use crate::{Card, CardsValues, Palos};
+
+/// Holding the Rey and the Caballo of the same palo, worth 20 points or 40 in the triunfo palo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cante {
+    pub palo: Palos,
+}
+
+impl Cante {
+    pub fn points(&self, triunfo: Palos) -> u16 {
+        if self.palo == triunfo {
+            40
+        } else {
+            20
+        }
+    }
+
+    pub fn cards(&self) -> [Card; 2] {
+        [
+            Card {
+                value: CardsValues::Rey,
+                palo: self.palo,
+            },
+            Card {
+                value: CardsValues::Caballo,
+                palo: self.palo,
+            },
+        ]
+    }
+}
+
+/// The cantes in `hand` that have not been sung yet, the one in the triunfo palo first
+pub fn available_cantes(hand: &[Card], sung: &[Palos], triunfo: Palos) -> Vec<Cante> {
+    let mut cantes = Palos::ALL
+        .iter()
+        .filter(|palo| !sung.contains(palo))
+        .map(|palo| Cante { palo: *palo })
+        .filter(|cante| cante.cards().iter().all(|card| hand.contains(card)))
+        .collect::<Vec<Cante>>();
+    cantes.sort_by_key(|cante| cante.palo != triunfo);
+    cantes
+}
+
+/// The figure held four times in `hand`, the Reyes or the Caballos, which is a tute
+pub fn tute(hand: &[Card]) -> Option<CardsValues> {
+    [CardsValues::Rey, CardsValues::Caballo]
+        .into_iter()
+        .find(|value| hand.iter().filter(|card| card.value == *value).count() == Palos::ALL.len())
+}
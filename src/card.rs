@@ -0,0 +1,285 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardsValues {
+    As,
+    Dos,
+    Tres,
+    Cuatro,
+    Cinco,
+    Seis,
+    Siete,
+    Sota,
+    Caballo,
+    Rey,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palos {
+    Espadas,
+    Bastos,
+    Copas,
+    Oros,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub value: CardsValues,
+    pub palo: Palos,
+}
+
+impl CardsValues {
+    pub const ALL: [CardsValues; 10] = [
+        CardsValues::As,
+        CardsValues::Dos,
+        CardsValues::Tres,
+        CardsValues::Cuatro,
+        CardsValues::Cinco,
+        CardsValues::Seis,
+        CardsValues::Siete,
+        CardsValues::Sota,
+        CardsValues::Caballo,
+        CardsValues::Rey,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardsValues::As => "As",
+            CardsValues::Dos => "Dos",
+            CardsValues::Tres => "Tres",
+            CardsValues::Cuatro => "Cuatro",
+            CardsValues::Cinco => "Cinco",
+            CardsValues::Seis => "Seis",
+            CardsValues::Siete => "Siete",
+            CardsValues::Sota => "Sota",
+            CardsValues::Caballo => "Caballo",
+            CardsValues::Rey => "Rey",
+        }
+    }
+
+    /// The character of the value in the short notation of a card
+    pub fn letter(&self) -> char {
+        match self {
+            CardsValues::As => 'A',
+            CardsValues::Dos => '2',
+            CardsValues::Tres => '3',
+            CardsValues::Cuatro => '4',
+            CardsValues::Cinco => '5',
+            CardsValues::Seis => '6',
+            CardsValues::Siete => '7',
+            CardsValues::Sota => 'S',
+            CardsValues::Caballo => 'C',
+            CardsValues::Rey => 'R',
+        }
+    }
+}
+
+impl Card {
+    pub fn emoji(&self) -> char {
+        match self.palo {
+            Palos::Espadas => '⚔',
+            Palos::Bastos => '🏏',
+            Palos::Copas => '🏆',
+            Palos::Oros => '🪙',
+        }
+    }
+    pub fn name(&self) -> &str {
+        self.value.name()
+    }
+
+    pub fn value(&self) -> u8 {
+        match self.value {
+            CardsValues::As => 11,
+            CardsValues::Tres => 10,
+            CardsValues::Rey => 4,
+            CardsValues::Caballo => 2,
+            CardsValues::Sota => 3,
+            _ => 0,
+        }
+    }
+
+    pub fn kill_power(&self) -> u8 {
+        // In guiñote the power of a card defeating another is not the same as the points value of the card
+        match self.value {
+            CardsValues::As => 12,
+            CardsValues::Tres => 11,
+            CardsValues::Rey => 10,
+            CardsValues::Sota => 9,
+            CardsValues::Caballo => 8,
+            CardsValues::Siete => 7,
+            CardsValues::Seis => 6,
+            CardsValues::Cinco => 5,
+            CardsValues::Cuatro => 4,
+            CardsValues::Dos => 3,
+        }
+    }
+}
+
+impl Palos {
+    pub const ALL: [Palos; 4] = [Palos::Espadas, Palos::Bastos, Palos::Copas, Palos::Oros];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palos::Espadas => "Espadas",
+            Palos::Bastos => "Bastos",
+            Palos::Copas => "Copas",
+            Palos::Oros => "Oros",
+        }
+    }
+
+    /// The character of the palo in the short notation of a card
+    pub fn letter(&self) -> char {
+        match self {
+            Palos::Espadas => 'E',
+            Palos::Bastos => 'B',
+            Palos::Copas => 'C',
+            Palos::Oros => 'O',
+        }
+    }
+}
+
+impl fmt::Display for Palos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Palos::Espadas => "⚔ Espadas",
+            Palos::Bastos => "🏏 Bastos",
+            Palos::Copas => "🏆 Copas",
+            Palos::Oros => "🪙 Oros",
+        })
+    }
+}
+
+/// The short notation of the value and the palo, "AO" or "3E", or with `{:#}` the long
+/// spanish form, "As de Oros"
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{} de {}", self.value.name(), self.palo.name())
+        } else {
+            write!(f, "{}{}", self.value.letter(), self.palo.letter())
+        }
+    }
+}
+
+/// Why a text is not a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    Empty,
+    UnknownValue(String),
+    UnknownPalo(String),
+    //neither the short notation nor "<value> de <palo>"
+    Malformed(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::Empty => f.write_str("No card given"),
+            ParseCardError::UnknownValue(value) => write!(
+                f,
+                "Unknown card value \"{}\", expected one of A 2 3 4 5 6 7 S C R or its name",
+                value
+            ),
+            ParseCardError::UnknownPalo(palo) => write!(
+                f,
+                "Unknown palo \"{}\", expected one of O C E B or Oros, Copas, Espadas, Bastos",
+                palo
+            ),
+            ParseCardError::Malformed(text) => write!(
+                f,
+                "\"{}\" is not a card, write it as \"AO\" or as \"As de Oros\"",
+                text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Parses the short notation and the long spanish form of `Display`, ignoring case
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ParseCardError::Empty);
+        }
+        let words = text.split_whitespace().collect::<Vec<&str>>();
+        let (value, palo) = match words[..] {
+            [short] => {
+                let mut chars = short.chars();
+                match (chars.next(), chars.next(), chars.next()) {
+                    (Some(value), Some(palo), None) => (value.to_string(), palo.to_string()),
+                    _ => return Err(ParseCardError::Malformed(text.to_string())),
+                }
+            }
+            [value, de, palo] if de.eq_ignore_ascii_case("de") => {
+                (value.to_string(), palo.to_string())
+            }
+            _ => return Err(ParseCardError::Malformed(text.to_string())),
+        };
+        let value = CardsValues::ALL
+            .into_iter()
+            .find(|candidate| matches_name(&value, candidate.letter(), candidate.name()))
+            .ok_or(ParseCardError::UnknownValue(value))?;
+        let palo = Palos::ALL
+            .into_iter()
+            .find(|candidate| matches_name(&palo, candidate.letter(), candidate.name()))
+            .ok_or(ParseCardError::UnknownPalo(palo))?;
+        Ok(Card { value, palo })
+    }
+}
+
+fn matches_name(text: &str, letter: char, name: &str) -> bool {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(single), None) => single.eq_ignore_ascii_case(&letter),
+        _ => text.eq_ignore_ascii_case(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Deck;
+
+    #[test]
+    fn display_parses_back() {
+        for card in Deck::new().cards() {
+            assert_eq!(card.to_string().parse(), Ok(*card));
+            assert_eq!(format!("{:#}", card).parse(), Ok(*card));
+        }
+    }
+
+    #[test]
+    fn parses_ignoring_case() {
+        let as_de_oros = Card {
+            value: CardsValues::As,
+            palo: Palos::Oros,
+        };
+        assert_eq!("ao".parse(), Ok(as_de_oros));
+        assert_eq!(" as DE oros ".parse(), Ok(as_de_oros));
+        assert_eq!("A de O".parse(), Ok(as_de_oros));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+        assert_eq!(
+            "ZO".parse::<Card>(),
+            Err(ParseCardError::UnknownValue("Z".to_string()))
+        );
+        assert_eq!(
+            "AX".parse::<Card>(),
+            Err(ParseCardError::UnknownPalo("X".to_string()))
+        );
+        assert_eq!(
+            "AOO".parse::<Card>(),
+            Err(ParseCardError::Malformed("AOO".to_string()))
+        );
+        assert_eq!(
+            "As of Oros".parse::<Card>(),
+            Err(ParseCardError::Malformed("As of Oros".to_string()))
+        );
+    }
+}
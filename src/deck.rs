@@ -0,0 +1,179 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Card, CardsValues, Palos};
+
+pub const HAND_SIZE: usize = 6;
+//cards that must be left at least on each side of a cut
+const CUT_MARGIN: usize = 4;
+
+/// The 40 card spanish deck used to play guiñote
+#[derive(Debug)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        let cards = Palos::ALL
+            .iter()
+            .flat_map(|palo| {
+                CardsValues::ALL.iter().map(|value| Card {
+                    value: *value,
+                    palo: *palo,
+                })
+            })
+            .collect();
+        Deck { cards }
+    }
+
+    /// The deck for a game of `players`. With three players the Dos de Oros is left out so
+    /// the talon can be drawn evenly
+    pub fn for_players(players: usize) -> Self {
+        let mut deck = Self::new();
+        if players == 3 {
+            let dos_de_oros = Card {
+                value: CardsValues::Dos,
+                palo: Palos::Oros,
+            };
+            deck.cards.retain(|card| *card != dos_de_oros);
+        }
+        deck
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Takes the top `at` cards and puts them under the rest
+    pub fn cut(&mut self, at: usize) {
+        let at = at.min(self.cards.len());
+        self.cards.rotate_right(at);
+    }
+
+    /// Takes `amount` cards from the top of the deck, or less if there are not enough left
+    pub fn deal(&mut self, amount: usize) -> Vec<Card> {
+        let amount = amount.min(self.cards.len());
+        self.cards.split_off(self.cards.len() - amount)
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shuffles a new deck, cutting it if `cut`, and deals a hand to each of the `players`
+/// starting from the `mano`. The rest is the talon. The hands are indexed by seat
+pub fn deal<R: Rng>(
+    players: usize,
+    mano: usize,
+    cut: bool,
+    rng: &mut R,
+) -> (Vec<Vec<Card>>, Talon) {
+    let mut deck = Deck::for_players(players);
+    deck.shuffle(rng);
+    if cut {
+        let at = rng.gen_range(CUT_MARGIN..=deck.cards.len() - CUT_MARGIN);
+        deck.cut(at);
+    }
+    let mut hands = vec![Vec::new(); players];
+    for seat in (0..players).map(|i| (mano + i) % players) {
+        hands[seat] = deck.deal(HAND_SIZE);
+    }
+    (hands, Talon::new(deck))
+}
+
+/// The stock left after dealing, with the card that sets the triunfo turned up under it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Talon {
+    cards: Vec<Card>,
+    triunfo_card: Option<Card>,
+}
+
+impl Talon {
+    /// Turns up the next card of the deck to set the triunfo, the rest of the deck is the talon
+    pub fn new(mut deck: Deck) -> Self {
+        let triunfo_card = deck.cards.pop();
+        Talon {
+            cards: deck.cards,
+            triunfo_card,
+        }
+    }
+
+    /// Draws the top card, the turned up triunfo card is always the last one to be drawn
+    pub fn draw(&mut self) -> Option<Card> {
+        self.cards.pop().or_else(|| self.triunfo_card.take())
+    }
+
+    /// The cards face down, the next one to be drawn last
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Puts `cards` face down in place of the ones left, the triunfo card stays
+    pub fn replace_cards(&mut self, cards: Vec<Card>) {
+        self.cards = cards;
+    }
+
+    pub fn triunfo_card(&self) -> Option<&Card> {
+        self.triunfo_card.as_ref()
+    }
+
+    /// Puts `card` face up in place of the triunfo card and returns the triunfo card
+    pub fn exchange_triunfo_card(&mut self, card: Card) -> Option<Card> {
+        self.triunfo_card.replace(card)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len() + self.triunfo_card.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// The cards in deck order, to compare sets of cards
+#[cfg(test)]
+pub(crate) fn sorted(mut cards: Vec<Card>) -> Vec<Card> {
+    cards.sort_by_key(|card| (card.palo as u8, card.value as u8));
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_deal() {
+        for players in 2..=4 {
+            let first = deal(players, 1, true, &mut StdRng::seed_from_u64(7));
+            let again = deal(players, 1, true, &mut StdRng::seed_from_u64(7));
+            let other = deal(players, 1, true, &mut StdRng::seed_from_u64(8));
+            assert_eq!(first, again);
+            assert_ne!(first, other);
+        }
+    }
+
+    #[test]
+    fn deals_the_whole_deck() {
+        for players in 2..=4 {
+            let (hands, talon) = deal(players, 0, false, &mut StdRng::seed_from_u64(1));
+            assert!(hands.iter().all(|hand| hand.len() == HAND_SIZE));
+            let mut dealt = hands.concat();
+            dealt.extend(talon.cards());
+            dealt.extend(talon.triunfo_card());
+            assert_eq!(
+                sorted(dealt),
+                sorted(Deck::for_players(players).cards().to_vec())
+            );
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    cante::Cante, deck::Talon, ruleset::RuleSet, scoring::HandScore, seats::Seats, Card,
+    CardsValues, Palos,
+};
+
+/// A change in a game. Folding every event of a game from its start rebuilds its state,
+/// see `GameState::replay`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    GameStarted {
+        seats: Seats,
+        rules: RuleSet,
+        cut_deck: bool,
+        seed: u64,
+    },
+    //the hands indexed by seat and the talon left, with the triunfo card turned up
+    Dealt {
+        dealer: usize,
+        hands: Vec<Vec<Card>>,
+        talon: Talon,
+    },
+    CardPlayed {
+        seat: usize,
+        card: Card,
+    },
+    TrickWon {
+        seat: usize,
+        points: u16,
+    },
+    CanteSung {
+        seat: usize,
+        cante: Cante,
+        points: u16,
+    },
+    TuteSung {
+        seat: usize,
+        figure: CardsValues,
+    },
+    Exchanged {
+        seat: usize,
+        card: Card,
+        triunfo_card: Card,
+    },
+    //the bazas of a finished hand and the triunfo it was played with
+    HandScored {
+        score: HandScore,
+        triunfo: Palos,
+    },
+    //nobody won with the points of each side in the hand, another one is dealt
+    Vueltas {
+        hand_points: Vec<u16>,
+    },
+    GameWon {
+        side: usize,
+    },
+}
+
+pub type Events = Vec<GameEvent>;
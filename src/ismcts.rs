@@ -0,0 +1,302 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{ai, knowledge::Knowledge, player::Observation, Action, Card, GameState};
+
+/// How much trying the less visited plays weighs against the rewards of the best ones
+const EXPLORATION: f64 = 0.7;
+/// The margin of a hand that is the whole reward, see `ai::hand_margins`
+const MAX_MARGIN: f64 = 400.0;
+
+/// How long the search thinks before playing, in iterations or in time. A time budget plays
+/// differently on a faster or busier computer, so only iterations replay the same games from
+/// the same seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+impl Budget {
+    pub const DEFAULT: Budget = Budget::Iterations(1000);
+
+    fn allows(&self, iterations: u32, started: Instant) -> bool {
+        //there is always one iteration, even if it rarely gets to play a card
+        iterations == 0
+            || match self {
+                Budget::Iterations(budget) => iterations < *budget,
+                Budget::Time(budget) => started.elapsed() < *budget,
+            }
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget::DEFAULT
+    }
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Budget::Iterations(iterations) => write!(f, "{} iterations", iterations),
+            Budget::Time(time) => write!(f, "{} ms", time.as_millis()),
+        }
+    }
+}
+
+/// A text that is neither a number of iterations nor a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBudgetError(pub String);
+
+impl fmt::Display for ParseBudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a budget, give a number of iterations like 1000 or a time like 500ms or 2s",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseBudgetError {}
+
+/// Parses "1000" as iterations, "500ms" or "2s" as time
+impl FromStr for Budget {
+    type Err = ParseBudgetError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseBudgetError(text.to_string());
+        let text = text.trim();
+        let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+            Some(index) => text.split_at(index),
+            None => (text, ""),
+        };
+        let number = number.parse::<u32>().map_err(|_| invalid())?;
+        if number == 0 {
+            return Err(invalid());
+        }
+        match unit {
+            "" => Ok(Budget::Iterations(number)),
+            "ms" => Ok(Budget::Time(Duration::from_millis(u64::from(number)))),
+            "s" => Ok(Budget::Time(Duration::from_secs(u64::from(number)))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A play in the tree, shared by every sample of the unseen cards in which it is legal
+#[derive(Debug)]
+struct Node {
+    //the seat that played and its card, none at the root
+    play: Option<(usize, Card)>,
+    children: Vec<usize>,
+    visits: u32,
+    //the sum of the rewards of the side that played
+    reward: f64,
+    //the iterations in which the card could be played
+    availability: u32,
+}
+
+impl Node {
+    fn new(play: Option<(usize, Card)>) -> Self {
+        Node {
+            play,
+            children: Vec::new(),
+            visits: 0,
+            reward: 0.0,
+            availability: 0,
+        }
+    }
+}
+
+/// The index in the hand of the card `seat` should play, by information set Monte Carlo tree
+/// search. Every iteration samples the unseen cards from what the seat knows, walks down the
+/// tree through the plays that are legal in that sample and adds one, plays the rest of the
+/// hand out with the heuristic and rewards the plays walked by how the hand ended
+pub fn search<R: Rng>(game: &GameState, seat: usize, budget: Budget, rng: &mut R) -> usize {
+    let playable = game.playable_cards(seat);
+    if playable.len() == 1 {
+        return playable[0];
+    }
+    let observation = Observation::new(game, seat);
+    let knowledge = Knowledge::observe(&observation);
+    let mut tree = vec![Node::new(None)];
+    let started = Instant::now();
+    let mut iterations = 0;
+    while budget.allows(iterations, started) {
+        let mut sample = knowledge.sample(&observation, rng);
+        iterate(&mut tree, &mut sample, rng);
+        iterations += 1;
+    }
+    //the most visited card is the one the search trusts most
+    let Some((_, card)) = tree[0]
+        .children
+        .iter()
+        .map(|&child| &tree[child])
+        .max_by_key(|node| node.visits)
+        .and_then(|node| node.play)
+    else {
+        //every sample ended the hand before the seat played, with a cante or a tute guessed
+        //in the hand of another seat
+        return ai::choose_card(game, seat);
+    };
+    game.hand(seat)
+        .iter()
+        .position(|held| *held == card)
+        .expect("the cards at the root are in the hand")
+}
+
+fn iterate<R: Rng>(tree: &mut Vec<Node>, game: &mut GameState, rng: &mut R) {
+    let mut path = vec![0];
+    let margins = loop {
+        if let Some(margins) = sing_and_exchange(game) {
+            break margins;
+        }
+        let node = *path.last().expect("the path starts at the root");
+        let seat = game.turn();
+        let cards = game
+            .playable_cards(seat)
+            .into_iter()
+            .map(|index| game.hand(seat)[index])
+            .collect::<Vec<Card>>();
+        let untried = cards
+            .iter()
+            .filter(|&&card| {
+                !tree[node]
+                    .children
+                    .iter()
+                    .any(|&child| tree[child].play == Some((seat, card)))
+            })
+            .copied()
+            .collect::<Vec<Card>>();
+        let expand = untried.choose(rng).copied();
+        if let Some(card) = expand {
+            tree.push(Node::new(Some((seat, card))));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+        }
+        for child in tree[node].children.clone() {
+            if matches!(tree[child].play, Some((_, card)) if cards.contains(&card)) {
+                tree[child].availability += 1;
+            }
+        }
+        let child = match expand {
+            Some(_) => *tree[node].children.last().expect("a child was just added"),
+            None => select(tree, node, &cards),
+        };
+        path.push(child);
+
+        let (_, card) = tree[child].play.expect("only the root has no play");
+        let index = game
+            .hand(seat)
+            .iter()
+            .position(|held| *held == card)
+            .expect("a legal card is in the hand");
+        let events = game
+            .apply(Action::Play { seat, card: index })
+            .expect("a legal card can be played");
+        if let Some(margins) = ai::hand_margins(game, &events) {
+            break margins;
+        }
+        if expand.is_some() {
+            break ai::play_out(game);
+        }
+    };
+    let seats = game.seats();
+    for node in path {
+        let node = &mut tree[node];
+        node.visits += 1;
+        if let Some((seat, _)) = node.play {
+            node.reward += reward(margins[seats.side(seat)]);
+        }
+    }
+}
+
+fn select(tree: &[Node], node: usize, cards: &[Card]) -> usize {
+    //the legal play with the best upper confidence bound, counting only the iterations in
+    //which it was legal
+    let bound = |child: &Node| {
+        let visits = f64::from(child.visits);
+        child.reward / visits + EXPLORATION * (f64::from(child.availability).ln() / visits).sqrt()
+    };
+    *tree[node]
+        .children
+        .iter()
+        .filter(|&&child| matches!(tree[child].play, Some((_, card)) if cards.contains(&card)))
+        .max_by(|&&a, &&b| bound(&tree[a]).total_cmp(&bound(&tree[b])))
+        .expect("every legal card has been tried")
+}
+
+fn sing_and_exchange(game: &mut GameState) -> Option<Vec<i32>> {
+    //the searches only choose cards, the rest is done as the heuristic would
+    loop {
+        let action = (0..game.seats().players())
+            .filter_map(|seat| ai::heuristic_action(game, seat))
+            .find(|action| !matches!(action, Action::Play { .. }))?;
+        let events = game
+            .apply(action)
+            .expect("the heuristic only takes legal actions");
+        if let Some(margins) = ai::hand_margins(game, &events) {
+            return Some(margins);
+        }
+    }
+}
+
+fn reward(margin: i32) -> f64 {
+    (f64::from(margin) / MAX_MARGIN).clamp(-1.0, 1.0) / 2.0 + 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{ruleset::RuleSet, seats::Seats};
+
+    #[test]
+    fn parses_budgets() {
+        assert_eq!("1000".parse(), Ok(Budget::Iterations(1000)));
+        assert_eq!(
+            "500ms".parse(),
+            Ok(Budget::Time(Duration::from_millis(500)))
+        );
+        assert_eq!(" 2s ".parse(), Ok(Budget::Time(Duration::from_secs(2))));
+        for text in ["", "0", "fast", "2m", "-5", "ms"] {
+            assert_eq!(
+                text.parse::<Budget>(),
+                Err(ParseBudgetError(text.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn displays_what_it_parses() {
+        assert_eq!(Budget::Iterations(200).to_string(), "200 iterations");
+        assert_eq!(
+            Budget::Time(Duration::from_millis(300)).to_string(),
+            "300 ms"
+        );
+    }
+
+    #[test]
+    fn searches_a_playable_card() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for players in 2..=4 {
+            let mut game = GameState::new(Seats::new(players), RuleSet::CLASICO, 0, true, 3);
+            //the search is asked wherever the heuristic plays a card
+            ai::play_out_heuristic(&mut game, |game, action| {
+                if let Action::Play { seat, .. } = action {
+                    let card = search(game, seat, Budget::Iterations(20), &mut rng);
+                    assert!(game.playable_cards(seat).contains(&card));
+                }
+                true
+            });
+        }
+    }
+}
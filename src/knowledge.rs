@@ -0,0 +1,233 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    deck::{Deck, HAND_SIZE},
+    player::Observation,
+    trick::Trick,
+    Card, GameEvent, GameState, Palos,
+};
+
+/// Shuffles of the unseen cards tried before giving up on the palos seats are known to lack
+const SAMPLE_ATTEMPTS: usize = 20;
+
+/// What a seat knows of the cards it can not see, from what everyone saw during the hand:
+/// the cards shown singing, the ones taken from the talon face up and the palos a seat
+/// showed it lacks by not following suit in the arrastre
+#[derive(Debug, Clone)]
+pub struct Knowledge {
+    seat: usize,
+    //cards known to be in the hand of each seat
+    known: Vec<Vec<Card>>,
+    voids: Vec<Vec<Palos>>,
+}
+
+impl Knowledge {
+    /// What the seat of `observation` knows, gathered from the log of the hand being played
+    pub fn observe(observation: &Observation) -> Self {
+        let seats = observation.seats();
+        let rules = observation.rules();
+        let mut knowledge = Knowledge {
+            seat: observation.seat(),
+            known: vec![Vec::new(); seats.players()],
+            voids: vec![Vec::new(); seats.players()],
+        };
+        //what is public of the talon and the trick as the events happened
+        let mut remaining = 0;
+        let mut face_up = None;
+        let mut triunfo = observation.triunfo();
+        let mut trick = Trick::new(0);
+        //the talon of a deal is only seen face up, the rest is what the hands leave of the deck
+        let dealt = Deck::for_players(seats.players()).cards().len() - seats.players() * HAND_SIZE;
+        for event in observation.log() {
+            match event {
+                GameEvent::Dealt { dealer, talon, .. } => {
+                    knowledge.known.iter_mut().for_each(Vec::clear);
+                    knowledge.voids.iter_mut().for_each(Vec::clear);
+                    remaining = dealt;
+                    face_up = talon.triunfo_card().copied();
+                    triunfo = face_up.map_or(triunfo, |card| card.palo);
+                    trick = Trick::new(seats.next(*dealer));
+                }
+                GameEvent::CardPlayed { seat, card } => {
+                    knowledge.known[*seat].retain(|known| known != card);
+                    if let (0, Some(led_palo), Some((winner, winning))) =
+                        (remaining, trick.led_palo(), trick.winning(triunfo))
+                    {
+                        //in the arrastre a seat follows suit and else trumps if it can
+                        let partner_winning =
+                            winner != *seat && seats.side(winner) == seats.side(*seat);
+                        if card.palo != led_palo {
+                            knowledge.lacks(*seat, led_palo);
+                            let had_to_trump = !rules.must_overtrump || winning.palo != triunfo;
+                            if card.palo != triunfo && had_to_trump && !partner_winning {
+                                knowledge.lacks(*seat, triunfo);
+                            }
+                        }
+                    }
+                    trick.play(*seat, *card);
+                }
+                GameEvent::TrickWon { seat, .. } => {
+                    //the last seat to draw takes the face up card
+                    for drawer in seats.from(*seat) {
+                        if remaining == 0 {
+                            break;
+                        }
+                        remaining -= 1;
+                        if remaining == 0 {
+                            knowledge.knows(drawer, face_up);
+                        }
+                    }
+                    trick = Trick::new(*seat);
+                }
+                GameEvent::CanteSung { seat, cante, .. } => {
+                    knowledge.knows(*seat, cante.cards());
+                }
+                GameEvent::Exchanged {
+                    seat,
+                    card,
+                    triunfo_card,
+                } => {
+                    knowledge.known[*seat].retain(|known| known != card);
+                    knowledge.knows(*seat, [*triunfo_card]);
+                    face_up = Some(*card);
+                }
+                _ => {}
+            }
+        }
+        knowledge
+    }
+
+    pub fn known(&self, seat: usize) -> &[Card] {
+        &self.known[seat]
+    }
+
+    pub fn voids(&self, seat: usize) -> &[Palos] {
+        &self.voids[seat]
+    }
+
+    /// The game with the cards the seat can not see dealt at random to the other hands and the
+    /// talon, keeping what it knows about them whenever that is possible
+    pub fn sample<R: Rng>(&self, observation: &Observation, rng: &mut R) -> GameState {
+        //the deck but for the cards in the hand of the seat, played in this hand, face up or
+        //known to be in another hand
+        let played = |card: &Card| {
+            observation
+                .hand_score()
+                .bazas
+                .iter()
+                .flatten()
+                .chain([observation.trick()])
+                .any(|trick| trick.cards().iter().any(|(_, played)| played == card))
+        };
+        let mut unseen = Deck::for_players(observation.seats().players())
+            .cards()
+            .iter()
+            .filter(|card| {
+                !observation.hand().contains(card)
+                    && !played(card)
+                    && observation.triunfo_card() != Some(*card)
+                    && !self.known.iter().flatten().any(|known| known == *card)
+            })
+            .copied()
+            .collect::<Vec<Card>>();
+        for _ in 0..SAMPLE_ATTEMPTS {
+            unseen.shuffle(rng);
+            if let Some((hands, talon)) = self.deal(observation, unseen.clone(), true) {
+                return GameState::imagined(observation, hands, talon);
+            }
+        }
+        let (hands, talon) = self
+            .deal(observation, unseen, false)
+            .expect("the unseen cards fill the hidden places");
+        GameState::imagined(observation, hands, talon)
+    }
+
+    fn knows(&mut self, seat: usize, cards: impl IntoIterator<Item = Card>) {
+        for card in cards {
+            if !self.known[seat].contains(&card) {
+                self.known[seat].push(card);
+            }
+        }
+    }
+
+    fn lacks(&mut self, seat: usize, palo: Palos) {
+        if !self.voids[seat].contains(&palo) {
+            self.voids[seat].push(palo);
+        }
+    }
+
+    fn deal(
+        &self,
+        observation: &Observation,
+        mut unseen: Vec<Card>,
+        keep_voids: bool,
+    ) -> Option<(Vec<Vec<Card>>, Vec<Card>)> {
+        //the seats lacking more palos choose first, the rest of the cards are the talon
+        let players = observation.seats().players();
+        let mut order = (0..players)
+            .filter(|&seat| seat != self.seat)
+            .collect::<Vec<usize>>();
+        order.sort_by_key(|&seat| std::cmp::Reverse(self.voids[seat].len()));
+        let mut hands = vec![Vec::new(); players];
+        hands[self.seat] = observation.hand().to_vec();
+        for seat in order {
+            let mut hand = self.known[seat].clone();
+            while hand.len() < observation.hand_size(seat) {
+                let index = unseen
+                    .iter()
+                    .position(|card| !keep_voids || !self.voids[seat].contains(&card.palo))?;
+                hand.push(unseen.remove(index));
+            }
+            hands[seat] = hand;
+        }
+        Some((hands, unseen))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{ai, deck::sorted, ruleset::RuleSet, seats::Seats};
+
+    /// The cards the hands and the talon of `game` hold between them
+    fn hidden(game: &GameState) -> Vec<Card> {
+        let mut cards = (0..game.seats().players())
+            .flat_map(|seat| game.hand(seat).to_vec())
+            .collect::<Vec<Card>>();
+        cards.extend(game.talon().cards());
+        cards.extend(game.talon().triunfo_card());
+        sorted(cards)
+    }
+
+    #[test]
+    fn samples_could_be_the_game() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for players in 2..=4 {
+            for (seed, rules) in RuleSet::PRESETS.into_iter().enumerate() {
+                let mut game = GameState::new(Seats::new(players), rules, 0, true, seed as u64);
+                ai::play_out_heuristic(&mut game, |game, _| {
+                    let seat = game.turn();
+                    let observation = Observation::new(game, seat);
+                    let knowledge = Knowledge::observe(&observation);
+                    let sample = knowledge.sample(&observation, &mut rng);
+                    assert_eq!(sample.hand(seat), game.hand(seat));
+                    assert_eq!(sample.talon().remaining(), game.talon().remaining());
+                    assert_eq!(hidden(&sample), hidden(game));
+                    for other in 0..players {
+                        assert_eq!(sample.hand(other).len(), game.hand(other).len());
+                        for card in knowledge.known(other) {
+                            assert!(game.hand(other).contains(card));
+                            assert!(sample.hand(other).contains(card));
+                        }
+                        for palo in knowledge.voids(other) {
+                            assert!(game.hand(other).iter().all(|card| card.palo != *palo));
+                        }
+                    }
+                    true
+                });
+            }
+        }
+    }
+}
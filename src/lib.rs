@@ -0,0 +1,26 @@
+//! The rules of guiñote, independent of any user interface. A `GameState` is driven by
+//! applying `Action`s to it, which either fail with a `RuleError` or return the `GameEvent`s
+//! they caused
+
+pub mod ai;
+pub mod cante;
+pub mod card;
+pub mod deck;
+pub mod event;
+pub mod ismcts;
+pub mod knowledge;
+pub mod matchplay;
+pub mod phase;
+pub mod player;
+pub mod rules;
+pub mod ruleset;
+pub mod scoring;
+pub mod seats;
+pub mod solver;
+pub mod state;
+pub mod trick;
+
+pub use card::{Card, CardsValues, Palos, ParseCardError};
+pub use event::{Events, GameEvent};
+pub use phase::GamePhase;
+pub use state::{Action, GameState, RuleError};
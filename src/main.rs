@@ -1,9 +1,7 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use ratatui::{
     buffer::Buffer,
-    crossterm::{
-        event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-        style::ContentStyle,
-    },
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     symbols::border,
@@ -15,193 +13,155 @@ use ratatui::{
     },
     Frame,
 };
-use std::io;
+use std::{env, fmt, fs, io, time::Duration};
+
+use guinotecli::{
+    ai::{Computer, Difficulty},
+    ismcts::Budget,
+    matchplay::{self, MatchScore},
+    player::{Observation, Player, Scripted},
+    ruleset::RuleSet,
+    scoring::{self, HandScore},
+    seats::{SeatPosition, Seats},
+    solver, Action, CardsValues, GameEvent, GamePhase, GameState, Palos,
+};
 
 mod game;
+mod stats;
+
+use stats::{GameRecord, Stats};
 
 const MIN_TERMINAL_WIDTH: u16 = 140;
 const MIN_TERMINAL_HEIGHT: u16 = 35;
 
 const CARD_WIDTH: u16 = 9;
 const CARD_HEIGHT: u16 = 6;
-
-#[derive(Debug)]
-enum CardsValues {
-    As,
-    Dos,
-    Tres,
-    Cuatro,
-    Cinco,
-    Seis,
-    Siete,
-    Sota,
-    Caballo,
-    Rey,
-}
-#[derive(Debug, PartialEq)]
-enum Palos {
-    Espadas,
-    Bastos,
-    Copas,
-    Oros,
+const SIDE_HAND_WIDTH: u16 = 18;
+const CARD_BACK: &str = "▒▒▒▒▒";
+
+//the time the computer waits before each of its actions, so they can be followed
+const COMPUTER_DELAY: Duration = Duration::from_millis(700);
+
+/// Who sits at a seat when a match starts
+#[derive(Debug, Clone)]
+pub enum Sitter {
+    //whoever is at the keyboard, only at the seat the table is seen from
+    You,
+    Computer(Difficulty),
+    Script(Scripted),
 }
 
-impl Card {
-    fn emoji(&self) -> char {
-        match self.palo {
-            Palos::Espadas => '⚔',
-            Palos::Bastos => '🏏',
-            Palos::Copas => '🏆',
-            Palos::Oros => '🪙',
-        }
-    }
-    fn name(&self) -> &str {
-        match self.value {
-            CardsValues::As => "As",
-            CardsValues::Dos => "Dos",
-            CardsValues::Tres => "Tres",
-            CardsValues::Cuatro => "Cuatro",
-            CardsValues::Cinco => "Cinco",
-            CardsValues::Seis => "Seis",
-            CardsValues::Siete => "Siete",
-            CardsValues::Sota => "Sota",
-            CardsValues::Caballo => "Caballo",
-            CardsValues::Rey => "Rey",
-        }
-    }
-
-    fn value(&self) -> u8 {
-        match self.value {
-            CardsValues::As => 11,
-            CardsValues::Tres => 10,
-            CardsValues::Rey => 4,
-            CardsValues::Caballo => 2,
-            CardsValues::Sota => 3,
-            _ => 0,
-        }
-    }
-
-    fn kill_power(&self) -> u8 {
-        // In guiñote the power of a card defeating another is not the same as the points value of the card
-        match self.value {
-            CardsValues::As => 12,
-            CardsValues::Tres => 11,
-            CardsValues::Rey => 10,
-            CardsValues::Sota => 9,
-            CardsValues::Caballo => 8,
-            CardsValues::Siete => 7,
-            CardsValues::Seis => 6,
-            CardsValues::Cinco => 5,
-            CardsValues::Cuatro => 4,
-            CardsValues::Dos => 3,
+impl fmt::Display for Sitter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sitter::You => f.write_str("You"),
+            Sitter::Computer(difficulty) => f.write_str(difficulty.name()),
+            Sitter::Script(_) => f.write_str("Script"),
         }
     }
 }
 
-impl Palos {
-    fn to_string(&self) -> String {
-        match self {
-            Palos::Espadas => "⚔ Espadas",
-            Palos::Bastos => "🏏 Bastos",
-            Palos::Copas => "🏆 Copas",
-            Palos::Oros => "🪙 Oros",
-        }
-        .to_string()
+/// The player at the keyboard, who acts with the keys pressed in the game
+#[derive(Debug, Default)]
+struct LocalPlayer {
+    pending: Option<Action>,
+}
+
+impl Player for LocalPlayer {
+    fn act(&mut self, _observation: &Observation) -> Option<Action> {
+        self.pending.take()
+    }
+
+    fn input(&mut self, action: Action) {
+        self.pending = Some(action);
     }
 }
 
 #[derive(Debug)]
 pub struct App {
-    points: u8,
-    opponent_points: u8,
+    //the table chosen in the menu, the next game is played with it
+    seats: Seats,
+    rules: RuleSet,
+    cut_deck: bool,
+    difficulty: Difficulty,
+    //how long the expert computer thinks
+    budget: Budget,
+    //tell the player how many points their plays in the arrastre lose, if any
+    analysis: bool,
+    //every game is shuffled from this seed, the same seed and keys play the same games
+    seed: u64,
+    rng: StdRng,
+    //the games dealt since the session started, the one being played is the last of them
+    games: u32,
+    //the random choices of the computer, also from the seed
+    computer_rng: StdRng,
+    //who sits at each seat if it is not the default, you at the first one and the computer
+    //at the difficulty of the menu at the others
+    sitters: Vec<Option<Sitter>>,
+    //the players of the match being played, one for each seat
+    players: Vec<Box<dyn Player>>,
+    game: GameState,
     exit: bool,
     current_screen: Screens,
-    opponent_cards: Vec<Card>,
-    player_cards: Vec<Card>,
-    selected_card: Option<u8>,
-    triunfo: Palos,
-    last_played_card: Option<Card>,
-    last_played_opponent_card: Option<Card>,
-    opponent_selected_card: Option<u8>,
+    //the finished hand with its triunfo while its bazas are shown, and the screen that follows
+    review: Option<(HandScore, Palos)>,
+    after_review: Screens,
+    //games and cotos won by each side, the game being played is not in it until it ends
+    match_score: MatchScore,
+    stats: Stats,
+    message: Option<String>,
     is_terminal_too_small: bool,
 }
-#[derive(Debug)]
-pub struct Card {
-    value: CardsValues,
-    palo: Palos,
-}
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub enum Screens {
+    #[default]
     Menu,
     Game,
-    OpponentWin,
-    Win,
+    //the outcome shown depends on how the game ended
+    GameOver,
+    HandReview,
     ResolutionError,
 }
 
-impl Default for Screens {
+impl Default for App {
     fn default() -> Self {
-        Screens::Menu
+        Self::new()
     }
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let seats = Seats::new(2);
+        let rules = RuleSet::default();
+        let mut rng = StdRng::seed_from_u64(seed);
         App {
             is_terminal_too_small: false,
-            points: 0,
-            opponent_points: 0,
+            seats,
+            rules,
+            cut_deck: false,
+            difficulty: Difficulty::default(),
+            budget: Budget::default(),
+            analysis: false,
+            computer_rng: StdRng::seed_from_u64(rng.gen()),
+            sitters: Vec::new(),
+            players: Vec::new(),
+            //the first hand is dealt so that the player is the mano
+            game: GameState::new(seats, rules, seats.previous(0), false, rng.gen()),
+            seed,
+            rng,
+            games: 0,
             exit: false,
             current_screen: Screens::Menu,
-            opponent_cards: vec![
-                Card {
-                    value: CardsValues::As,
-                    palo: Palos::Bastos,
-                },
-                Card {
-                    value: CardsValues::Dos,
-                    palo: Palos::Bastos,
-                },
-                Card {
-                    value: CardsValues::Tres,
-                    palo: Palos::Espadas,
-                },
-                Card {
-                    value: CardsValues::Cuatro,
-                    palo: Palos::Oros,
-                },
-                Card {
-                    value: CardsValues::Sota,
-                    palo: Palos::Copas,
-                },
-            ],
-            player_cards: vec![
-                Card {
-                    value: CardsValues::As,
-                    palo: Palos::Copas,
-                },
-                Card {
-                    value: CardsValues::Dos,
-                    palo: Palos::Copas,
-                },
-                Card {
-                    value: CardsValues::Tres,
-                    palo: Palos::Copas,
-                },
-                Card {
-                    value: CardsValues::Cuatro,
-                    palo: Palos::Copas,
-                },
-            ],
-            selected_card: Some(3),
-            last_played_card: Some(Card {
-                value: CardsValues::Cuatro,
-                palo: Palos::Bastos,
-            }),
-            last_played_opponent_card: None,
-            opponent_selected_card: None,
-            triunfo: Palos::Copas,
+            review: None,
+            after_review: Screens::Game,
+            match_score: MatchScore::new(seats.sides(), 1),
+            stats: Stats::load(),
+            message: None,
         }
     }
     pub fn run(&mut self, terminal: &mut game::Tui) -> io::Result<()> {
@@ -216,22 +176,21 @@ impl App {
         frame.render_widget(self, frame.size())
     }
 
-    fn clean_selected(&mut self) {
-        self.selected_card = None;
-        self.opponent_selected_card = None;
-    }
-
     fn handle_events(&mut self) -> io::Result<()> {
+        //the other players act unless a key is pressed while they wait
+        if matches!(self.current_screen, Screens::Game) && !event::poll(COMPUTER_DELAY)? {
+            if let Some(action) = self.computer_action() {
+                self.apply_computer_action(action);
+            }
+            return Ok(());
+        }
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event)
             }
             Event::Resize(width, height) => {
-                if height < MIN_TERMINAL_HEIGHT || width < MIN_TERMINAL_WIDTH {
-                    self.is_terminal_too_small = true;
-                } else {
-                    self.is_terminal_too_small = false;
-                }
+                self.is_terminal_too_small =
+                    height < MIN_TERMINAL_HEIGHT || width < MIN_TERMINAL_WIDTH;
             }
             _ => {}
         };
@@ -241,64 +200,137 @@ impl App {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
             KeyCode::Char('q') => self.exit(),
-            KeyCode::Left => self.decrement_points(),
-            KeyCode::Right => self.increment_points(),
-            KeyCode::Enter => self.set_screen(Screens::Game),
+            code => match self.current_screen {
+                Screens::Menu => self.handle_menu_key(code),
+                Screens::Game => self.handle_game_key(code),
+                Screens::HandReview => self.handle_review_key(code),
+                Screens::GameOver => self.handle_end_key(code),
+                _ => {}
+            },
+        }
+    }
+
+    fn handle_menu_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.start_match(),
+            KeyCode::Char('r') => self.rules = self.rules.next_preset(),
+            KeyCode::Char('k') => self.cut_deck = !self.cut_deck,
+            KeyCode::Char('a') => self.analysis = !self.analysis,
+            KeyCode::Char('d') => {
+                self.difficulty = match self.difficulty.next() {
+                    Difficulty::Expert(_) => Difficulty::Expert(self.budget),
+                    difficulty => difficulty,
+                }
+            }
+            KeyCode::Char('m') => {
+                let cotos_to_win = self.match_score.cotos_to_win % 3 + 1;
+                self.match_score = MatchScore::new(self.seats.sides(), cotos_to_win);
+            }
+            KeyCode::Char('p') => {
+                let players = self.seats.players() % 3 + 2;
+                self.seats = Seats::new(players);
+                self.match_score =
+                    MatchScore::new(self.seats.sides(), self.match_score.cotos_to_win);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_game_key(&mut self, code: KeyCode) {
+        match code {
             //Player card select
             KeyCode::Char('1') => self.select_card(0),
             KeyCode::Char('2') => self.select_card(1),
             KeyCode::Char('3') => self.select_card(2),
             KeyCode::Char('4') => self.select_card(3),
-            //DEBUG
-            KeyCode::Char('5') => self.set_screen(Screens::OpponentWin),
-            KeyCode::Char('6') => self.set_screen(Screens::Win),
-            //Opponent card select
-            KeyCode::Char('7') => self.opponent_select_card(0),
-            KeyCode::Char('8') => self.opponent_select_card(1),
-            KeyCode::Char('9') => self.opponent_select_card(2),
-            KeyCode::Char('0') => self.opponent_select_card(3),
-            KeyCode::Char('\'') => self.opponent_select_card(4),
-            KeyCode::Char('¡') => self.opponent_select_card(5),
+            KeyCode::Char('5') => self.select_card(4),
+            KeyCode::Char('6') => self.select_card(5),
+            KeyCode::Char('c') => self.input(Action::Sing { seat: 0 }),
+            KeyCode::Char('s') => self.input(Action::Exchange { seat: 0 }),
+            KeyCode::Char('t') => self.input(Action::SingTute { seat: 0 }),
             _ => {}
         }
     }
 
-    fn add_points(&mut self, cards: Vec<Card>, target: u8) {
-        //if 0 add points to player, if 1 add points to opponent
-        let points_to_add: u8 = cards
-            .iter()
-            .map(|card| match card.value {
-                CardsValues::Sota => 3,
-                CardsValues::Caballo => 2,
-                CardsValues::Rey => 4,
-                CardsValues::As => 11,
-                CardsValues::Tres => 10,
-                _ => 0,
-            })
-            .sum();
-        if target == 0 {
-            self.points += points_to_add;
-        } else {
-            self.opponent_points += points_to_add;
+    fn handle_review_key(&mut self, code: KeyCode) {
+        if code == KeyCode::Enter {
+            self.review = None;
+            self.current_screen = std::mem::take(&mut self.after_review);
         }
     }
 
-    fn do_x_defeat_y(&self, x: &Card, y: &Card) -> bool {
-        let triunfo = &self.triunfo;
-        #[allow(non_snake_case)]
-        let Y_palo = &y.palo;
-        #[allow(non_snake_case)]
-        let X_palo = &x.palo;
-        if X_palo == triunfo && Y_palo != triunfo {
-            return true;
+    fn handle_end_key(&mut self, code: KeyCode) {
+        if code == KeyCode::Char('n') {
+            self.next_game();
         }
-        if X_palo == Y_palo {
-            return x.kill_power() > y.kill_power();
+    }
+
+    fn start_match(&mut self) {
+        self.match_score = MatchScore::new(self.seats.sides(), self.match_score.cotos_to_win);
+        self.players = (0..self.seats.players())
+            .map(|seat| -> Box<dyn Player> {
+                match self.sitter(seat) {
+                    Sitter::You => Box::new(LocalPlayer::default()),
+                    Sitter::Computer(difficulty) => {
+                        Box::new(Computer::new(difficulty, self.computer_rng.gen()))
+                    }
+                    Sitter::Script(script) => Box::new(script),
+                }
+            })
+            .collect();
+        self.start_game(self.seats.previous(0));
+    }
+
+    fn sitter(&self, seat: usize) -> Sitter {
+        match self.sitters.get(seat).cloned().flatten() {
+            Some(sitter) => sitter,
+            None if seat == 0 => Sitter::You,
+            None => Sitter::Computer(self.difficulty),
         }
-        if X_palo != triunfo && Y_palo == triunfo {
-            return false;
+    }
+
+    /// Whether you play the first seat, or the computer or a script play it for you
+    fn is_at_keyboard(&self) -> bool {
+        matches!(self.sitters.first(), None | Some(None | Some(Sitter::You)))
+    }
+
+    /// Whether you play against the computer at the difficulty of the menu, the only games
+    /// kept in the stats
+    fn is_default_seating(&self) -> bool {
+        self.sitters
+            .iter()
+            .take(self.seats.players())
+            .all(Option::is_none)
+    }
+
+    fn next_game(&mut self) {
+        //once the match is over the next game starts a new one, the deal keeps rotating
+        if self.match_score.is_over() {
+            self.match_score = MatchScore::new(self.seats.sides(), self.match_score.cotos_to_win);
         }
-        x.kill_power() > y.kill_power()
+        self.start_game(self.seats.next(self.game.dealer()));
+    }
+
+    fn start_game(&mut self, dealer: usize) {
+        self.games += 1;
+        self.game = GameState::new(
+            self.seats,
+            self.rules,
+            dealer,
+            self.cut_deck,
+            self.rng.gen(),
+        );
+        self.message = self.deal_message();
+        self.set_screen(Screens::Game);
+    }
+
+    fn deal_message(&self) -> Option<String> {
+        let cutter = self.game.cutter()?;
+        Some(format!(
+            "{} cut the deck and {} dealt",
+            self.seats.name(cutter),
+            self.seats.name(self.game.dealer())
+        ))
     }
 
     fn set_screen(&mut self, screen: Screens) {
@@ -309,36 +341,283 @@ impl App {
         self.exit = true;
     }
 
-    fn increment_points(&mut self) {
-        self.points += 1;
+    fn select_card(&mut self, card: usize) {
+        if !self.is_at_keyboard() {
+            return;
+        }
+        let mistake = if self.analysis && self.game.turn() == 0 {
+            solver::mistake(&self.game, card)
+        } else {
+            None
+        };
+        let best = mistake
+            .as_ref()
+            .map(|mistake| self.game.hand(0)[mistake.best.card]);
+        self.input(Action::Play { seat: 0, card });
+        if let (Some(mistake), Some(best), None) = (mistake, best, &self.message) {
+            self.message = Some(format!(
+                "That was a mistake worth {} points, the {} of {} was better",
+                mistake.cost,
+                best.name(),
+                best.palo
+            ));
+        }
+    }
+
+    /// Gives an action chosen with the keys to the player at the keyboard, who takes it at once
+    fn input(&mut self, action: Action) {
+        if !self.is_at_keyboard() {
+            return;
+        }
+        self.players[0].input(action);
+        if let Some(action) = self.players[0].act(&Observation::new(&self.game, 0)) {
+            self.apply(action);
+        }
+    }
+
+    /// The next action of the players that do not wait for the keys
+    fn computer_action(&mut self) -> Option<Action> {
+        let local = self.is_at_keyboard();
+        let game = &self.game;
+        self.players
+            .iter_mut()
+            .enumerate()
+            .filter(|(seat, _)| *seat != 0 || !local)
+            .find_map(|(seat, player)| player.act(&Observation::new(game, seat)))
+    }
+
+    fn apply_computer_action(&mut self, action: Action) {
+        //the message stays until something else is worth telling, the player may not have read it
+        let message = self.message.take();
+        self.apply(action);
+        if self.message.is_none() {
+            self.message = message;
+        }
+    }
+
+    fn apply(&mut self, action: Action) {
+        match self.game.apply(action) {
+            Ok(events) => {
+                self.message = None;
+                for event in events {
+                    self.handle_game_event(event);
+                }
+            }
+            Err(error) => self.message = Some(error.to_string()),
+        }
+        //the bazas of a finished hand are shown before whatever comes after it
+        if self.review.is_some() && !matches!(self.current_screen, Screens::HandReview) {
+            self.after_review = std::mem::replace(&mut self.current_screen, Screens::HandReview);
+        }
+    }
+
+    fn handle_game_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::CanteSung {
+                seat,
+                cante,
+                points,
+            } => {
+                self.message = Some(format!(
+                    "{} sang {} in {}",
+                    self.seats.name(seat),
+                    points,
+                    cante.palo
+                ));
+            }
+            GameEvent::Exchanged {
+                seat,
+                card,
+                triunfo_card,
+            } => {
+                self.message = Some(format!(
+                    "{} exchanged the {} for the {} of {}",
+                    self.seats.name(seat),
+                    card.name(),
+                    triunfo_card.name(),
+                    triunfo_card.palo
+                ));
+            }
+            GameEvent::HandScored { score, triunfo } => self.review = Some((score, triunfo)),
+            GameEvent::Vueltas { hand_points } => {
+                let hand_points = hand_points
+                    .iter()
+                    .map(|points| points.to_string())
+                    .collect::<Vec<String>>();
+                self.message = Some(format!(
+                    "Nobody reached {}, vueltas! The hand ended {}",
+                    self.rules.winning_points,
+                    hand_points.join(" - ")
+                ));
+            }
+            GameEvent::Dealt { .. } if self.message.is_none() => {
+                self.message = self.deal_message();
+            }
+            GameEvent::GameWon { side } => self.finish_game(side),
+            _ => {}
+        }
     }
 
-    fn decrement_points(&mut self) {
-        self.points -= 1;
+    fn finish_game(&mut self, winner: usize) {
+        self.match_score.record_game(winner);
+        if self.is_default_seating() {
+            let record = GameRecord {
+                difficulty: self.difficulty,
+                players: self.seats.players(),
+                won: winner == self.seats.side(0),
+                points: self.game.points().to_vec(),
+                seed: self.seed,
+                game: self.games,
+            };
+            if let Err(error) = self.stats.record(record) {
+                self.message = Some(format!("The stats could not be saved: {}", error));
+            }
+        }
+        self.set_screen(Screens::GameOver);
     }
-    fn select_card(&mut self, card: u8) {
-        self.selected_card = Some(card);
+}
+
+impl App {
+    fn final_score(&self) -> String {
+        self.game
+            .points()
+            .iter()
+            .map(|points| points.to_string())
+            .collect::<Vec<String>>()
+            .join(" - ")
     }
 
-    fn opponent_select_card(&mut self, card: u8) {
-        self.opponent_selected_card = Some(card);
+    /// Who sits at each seat of the table chosen in the menu
+    fn seating(&self) -> String {
+        (0..self.seats.players())
+            .map(|seat| self.sitter(seat).to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
     }
 
-    fn get_selected_card(&self, opponent: bool) -> Option<&Card> {
-        //return the player selected card if false, else return the opponent selected card
-        if opponent {
-            self.opponent_selected_card
-                .map(|card| &self.opponent_cards[card as usize])
+    fn game_name(&self) -> String {
+        if self.is_default_seating() {
+            format!("Game vs {}", self.difficulty)
         } else {
-            self.selected_card
-                .map(|card| &self.player_cards[card as usize])
+            format!("Game of {}", self.seating())
         }
     }
+
+    fn side_name(&self, side: usize) -> &'static str {
+        match (self.seats.is_partnership(), side == self.seats.side(0)) {
+            (true, true) => "Your team",
+            (true, false) => "The other team",
+            (false, _) => self.seats.name(side),
+        }
+    }
+
+    fn match_progress(&self) -> Vec<Line<'static>> {
+        let join = |counts: &[u8]| {
+            counts
+                .iter()
+                .map(|count| count.to_string())
+                .collect::<Vec<String>>()
+                .join(" - ")
+        };
+        let mut lines = vec![
+            Line::from(format!(
+                "Cotos {} (first to {}), games in this coto {}",
+                join(&self.match_score.cotos),
+                self.match_score.cotos_to_win,
+                join(&self.match_score.games)
+            )),
+            Line::from(format!("Seed {}, game {}", self.seed, self.games)).dim(),
+        ];
+        match self.match_score.winner() {
+            Some(side) => {
+                lines.push(Line::from(format!("{} won the match!", self.side_name(side))).bold());
+                lines.push(Line::from(
+                    "Press 'n' to start a new match, 'q' to quit the game",
+                ));
+            }
+            None => lines.push(Line::from(
+                "Press 'n' for the next game, 'q' to quit the game",
+            )),
+        }
+        lines
+    }
+
+    fn render_baza(&self, side: usize, area: Rect, buf: &mut Buffer) {
+        //every trick in the baza of `side` with its points, the winning card highlighted
+        let Some((hand_score, triunfo)) = &self.review else {
+            return;
+        };
+        let baza = &hand_score.bazas[side];
+        let mut lines = baza
+            .iter()
+            .enumerate()
+            .map(|(i, trick)| {
+                let winner = trick.winner(*triunfo);
+                let mut spans = vec![format!("{:>2}. ", i + 1).dim()];
+                for (seat, card) in trick.cards() {
+                    let label = format!("{} {} ", card.name(), card.emoji());
+                    spans.push(if Some(*seat) == winner {
+                        label.yellow().bold()
+                    } else {
+                        label.into()
+                    });
+                }
+                spans.push(format!(" {}", trick.points()).bold());
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>();
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "Cards {}",
+            hand_score.card_points(side)
+        )));
+        lines.push(Line::from(format!("Cantes {}", hand_score.cantes[side])));
+        if hand_score.ultimas == Some(side) {
+            lines.push(Line::from(format!("Últimas {}", scoring::ULTIMAS)));
+        }
+        if let Some((capote_side, bonus)) = hand_score.capote {
+            if capote_side == side {
+                lines.push(Line::from(format!("Capote +{}", bonus)));
+            }
+        }
+        lines.push(Line::from(format!("Hand total {}", hand_score.total(side))).bold());
+        let block = Block::bordered().border_set(border::PLAIN).title(
+            Title::from(format!(" {}: {} tricks ", self.side_name(side), baza.len()))
+                .alignment(Alignment::Center),
+        );
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    fn render_side_hand(&self, seat: usize, area: Rect, buf: &mut Buffer) {
+        //the hands at the sides of the table are listed face down, but for the sung cantes
+        let lines = self
+            .game
+            .hand(seat)
+            .iter()
+            .map(|card| {
+                if self.game.revealed().contains(card) {
+                    Line::from(vec![
+                        format!("{} {}", card.name(), card.emoji()).into(),
+                        " cante".yellow().bold(),
+                    ])
+                } else {
+                    Line::from(CARD_BACK).red()
+                }
+            })
+            .collect::<Vec<Line>>();
+        let mut hand_block = Block::bordered()
+            .border_set(border::PLAIN)
+            .title(Title::from(self.seats.name(seat)).alignment(Alignment::Center));
+        if self.game.turn() == seat {
+            hand_block = hand_block.border_set(border::DOUBLE);
+        }
+        Paragraph::new(lines).block(hand_block).render(area, buf);
+    }
 }
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if (self.is_terminal_too_small) {
+        if self.is_terminal_too_small {
             let text = vec![
                 Line::from("The terminal size is too small to play the game"),
                 Line::from(format!(
@@ -363,9 +642,8 @@ impl Widget for &App {
         .::::.
         @\\/W\/\/W\//@
         \\/^\/\/^\//
-        \_O_<>_O_/
-        "#
-        .to_string();
+        \_O_<>_O_/"#
+            .to_string();
 
         let current_screen = &self.current_screen;
 
@@ -374,23 +652,6 @@ impl Widget for &App {
             .margin(0)
             .split(area);
 
-        let title = Title::from(" Menu ".bold());
-        let instructions = Title::from(Line::from(vec![" Quit ".into(), "<Q> ".blue().bold()]));
-        let block = Block::bordered()
-            .title(title.alignment(Alignment::Center))
-            .title(
-                instructions
-                    .alignment(Alignment::Center)
-                    .position(Position::Bottom),
-            )
-            .border_set(border::THICK);
-
-        let startGameBlock = Block::bordered().border_set(border::THICK);
-        let startGameText = Text::from(vec![Line::from(vec![
-            "Start Game".into(),
-            "<Enter>".blue().bold(),
-        ])]);
-
         match current_screen {
             Screens::Menu => {
                 let title = Title::from(" Menu ".bold());
@@ -407,6 +668,32 @@ impl Widget for &App {
                 Paragraph::new(vec![
                     Line::from("Start new Game"),
                     Line::from("<Enter>").blue().bold(),
+                    Line::from(""),
+                    Line::from(format!("Players: {}", self.seats.players())),
+                    Line::from(self.seating()).dim(),
+                    Line::from("<P>").blue().bold(),
+                    Line::from(format!("Rules: {}", self.rules.name)),
+                    Line::from(rules_summary(&self.rules)).dim(),
+                    Line::from("<R>").blue().bold(),
+                    Line::from(format!(
+                        "Match: first to {} cotos of {} games",
+                        self.match_score.cotos_to_win,
+                        matchplay::GAMES_PER_COTO
+                    )),
+                    Line::from("<M>").blue().bold(),
+                    Line::from(format!(
+                        "Cut the deck before dealing: {}",
+                        if self.cut_deck { "yes" } else { "no" }
+                    )),
+                    Line::from("<K>").blue().bold(),
+                    Line::from(format!("Computer: {}", self.difficulty)),
+                    Line::from(difficulty_summary(self.difficulty, &self.stats)).dim(),
+                    Line::from("<D>").blue().bold(),
+                    Line::from(format!(
+                        "Analysis of your plays in the arrastre: {}",
+                        if self.analysis { "on" } else { "off" }
+                    )),
+                    Line::from("<A>").blue().bold(),
                 ])
                 .alignment(Alignment::Center)
                 .block(block)
@@ -427,11 +714,34 @@ impl Widget for &App {
                     .split(parent_layout[0]);
                 let block = Block::bordered().border_set(border::PLAIN);
 
-                let title = Title::from(" Game ".bold());
-                let instructions =
-                    Title::from(Line::from(vec![" Quit ".into(), "<Q> ".blue().bold()]));
+                let title = if self.game.is_vueltas() {
+                    format!(" {} - Vueltas, {} ", self.game_name(), self.game.phase())
+                } else {
+                    format!(" {} - {} ", self.game_name(), self.game.phase())
+                };
+                let title = Title::from(title.bold());
+                let mut instructions = vec![" Quit ".into(), "<Q> ".blue().bold()];
+                if let Some(cante) = self.game.available_cantes(0).first() {
+                    instructions
+                        .push(format!(" Sing {} ", cante.points(self.game.triunfo())).into());
+                    instructions.push("<C> ".blue().bold());
+                }
+                if self.game.tute_figure(0).is_some() {
+                    instructions.push(" Sing tute ".into());
+                    instructions.push("<T> ".blue().bold());
+                }
+                if let Some(index) = self.game.exchangeable_card(0) {
+                    let name = self.game.hand(0)[index].name();
+                    instructions.push(format!(" Exchange the {} ", name).into());
+                    instructions.push("<S> ".blue().bold());
+                }
+                let instructions = Title::from(Line::from(instructions));
                 let parent_block = Block::bordered()
                     .title(title.alignment(Alignment::Center))
+                    .title(
+                        Title::from(format!(" Seed {}, game {} ", self.seed, self.games))
+                            .alignment(Alignment::Right),
+                    )
                     .title(
                         instructions
                             .alignment(Alignment::Center)
@@ -441,64 +751,53 @@ impl Widget for &App {
 
                 let top_game_layout = Layout::default()
                     .direction(Direction::Horizontal)
-                    .constraints([Constraint::Percentage(10), Constraint::Percentage(90)])
+                    .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
                     .split(game_layout[0]);
 
                 //RENDER CARDS OF THE TOP
-                let top_game_cards_layout = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints::<&Vec<Constraint>>(
-                        (0..self.opponent_cards.len())
-                            .map(|_| Constraint::Percentage(100 / self.opponent_cards.len() as u16))
-                            .collect::<Vec<Constraint>>()
-                            .as_ref(),
-                    )
-                    .split(top_game_layout[1]);
-
-                let card_block = Block::default().on_red();
-                for (i, card) in self.opponent_cards.iter().enumerate() {
-                    //let card_area = centered_rect(40, 60, top_game_cards_layout[i]);
-                    let card_button = match i {
-                        0 => "7",
-                        1 => "8",
-                        2 => "9",
-                        3 => "0",
-                        4 => "'",
-                        _ => "¡",
-                    };
-
-                    let mut card_block = card_block.clone().title(
-                        Title::from(card_button)
-                            .alignment(Alignment::Center)
-                            .position(Position::Bottom),
-                    );
-
-                    if let Some(slctd) = self.opponent_selected_card {
-                        let i = i as u8;
-                        if i == slctd {
-                            card_block = Block::bordered()
-                                .on_red()
-                                .title(
-                                    Title::from(card_button)
-                                        .alignment(Alignment::Center)
-                                        .position(Position::Bottom),
-                                )
-                                .border_set(border::DOUBLE);
+                if let Some(top_seat) = self.seats.seat_at(SeatPosition::Top) {
+                    let top_cards = self.game.hand(top_seat);
+                    let top_game_cards_layout = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints::<&Vec<Constraint>>(
+                            (0..top_cards.len())
+                                .map(|_| Constraint::Percentage(100 / top_cards.len() as u16))
+                                .collect::<Vec<Constraint>>()
+                                .as_ref(),
+                        )
+                        .split(top_game_layout[1]);
+
+                    let card_block = Block::default().on_red();
+                    for (i, card) in top_cards.iter().enumerate() {
+                        //let card_area = centered_rect(40, 60, top_game_cards_layout[i]);
+                        let mut card_block = card_block.clone();
+
+                        let card_area = center(
+                            top_game_cards_layout[i],
+                            Constraint::Length(CARD_WIDTH),
+                            Constraint::Length(CARD_HEIGHT),
+                        );
+                        //face down unless it was shown singing a cante
+                        let mut card_text = Text::from(vec![Line::from(CARD_BACK); 2]);
+                        if self.game.revealed().contains(card) {
+                            card_text = Text::from(vec![
+                                Line::from(card.name().to_string()),
+                                Line::from(card.emoji().to_string()),
+                            ]);
+                            card_block = card_block.title(
+                                Title::from("cante".yellow().bold()).alignment(Alignment::Center),
+                            );
                         }
+                        Paragraph::new(card_text)
+                            .alignment(Alignment::Center)
+                            .block(card_block.clone())
+                            .render(card_area, buf);
                     }
-                    let card_area = center(
-                        top_game_cards_layout[i],
-                        Constraint::Length(CARD_WIDTH),
-                        Constraint::Length(CARD_HEIGHT),
-                    );
-                    let card_text = Text::from(vec![
-                        Line::from(card.name().to_string()),
-                        Line::from(card.emoji().to_string()),
-                    ]);
-                    Paragraph::new(card_text)
+
+                    Paragraph::new(format!("{} Cards", self.seats.name(top_seat)))
                         .alignment(Alignment::Center)
-                        .block(card_block.clone())
-                        .render(card_area, buf);
+                        .block(block.clone())
+                        .render(top_game_layout[1], buf);
                 }
 
                 //RENDER POINTS AND OPPONENT CARDS
@@ -522,31 +821,65 @@ impl Widget for &App {
                     .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
                     .split(vertically_divided_top_part_layout_wo_margin[1]);
 
-                Paragraph::new(vec![
-                    Line::from("Yours"),
-                    Line::from(self.points.to_string()).alignment(Alignment::Center),
-                ])
-                .alignment(Alignment::Right)
-                .block(block.clone())
-                .render(horizontally_divided_info_box[0], buf);
+                if self.seats.sides() == 2 {
+                    Paragraph::new(vec![
+                        Line::from("Yours"),
+                        Line::from(self.game.score(0).to_string()).alignment(Alignment::Center),
+                    ])
+                    .alignment(Alignment::Right)
+                    .block(block.clone())
+                    .render(horizontally_divided_info_box[0], buf);
 
-                Paragraph::new(vec![
-                    Line::from("Opponent"),
-                    Line::from(self.points.to_string()).alignment(Alignment::Center),
-                ])
-                .alignment(Alignment::Left)
-                .block(block.clone())
-                .render(horizontally_divided_info_box[1], buf);
+                    Paragraph::new(vec![
+                        Line::from("Opponent"),
+                        Line::from(self.game.score(1).to_string()).alignment(Alignment::Center),
+                    ])
+                    .alignment(Alignment::Left)
+                    .block(block.clone())
+                    .render(horizontally_divided_info_box[1], buf);
+                } else {
+                    //everyone scores on their own, one line each
+                    Paragraph::new(
+                        (0..self.seats.sides())
+                            .map(|side| {
+                                Line::from(format!(
+                                    "{} {}",
+                                    self.seats.short_name(side),
+                                    self.game.score(side)
+                                ))
+                            })
+                            .collect::<Vec<Line>>(),
+                    )
+                    .alignment(Alignment::Center)
+                    .block(Block::default().padding(Padding::vertical(1)))
+                    .render(vertically_divided_top_part_layout_wo_margin[1], buf);
+                }
 
                 Block::bordered()
                     .border_set(border::ROUNDED)
                     .title(Title::from("Points").alignment(Alignment::Center))
+                    .title(
+                        Title::from(format!(
+                            "Dealer {}, mano {}",
+                            self.seats.short_name(self.game.dealer()),
+                            self.seats.short_name(self.game.mano())
+                        ))
+                        .alignment(Alignment::Center)
+                        .position(Position::Bottom),
+                    )
                     .on_blue()
                     .render(vertically_divided_top_part_layout_wo_margin[1], buf);
 
+                let triunfo_card = match self.game.talon().triunfo_card() {
+                    Some(card) => format!("{} {}", card.name(), card.emoji()),
+                    None => "-".to_string(),
+                };
                 Paragraph::new(vec![
                     Line::from("Triunfo").alignment(Alignment::Center),
-                    Line::from(self.triunfo.to_string()).alignment(Alignment::Center),
+                    Line::from(self.game.triunfo().to_string()).alignment(Alignment::Center),
+                    Line::from(triunfo_card).alignment(Alignment::Center),
+                    Line::from(format!("Talon: {}", self.game.talon().remaining()))
+                        .alignment(Alignment::Center),
                 ])
                 .render(
                     center(
@@ -557,25 +890,20 @@ impl Widget for &App {
                     buf,
                 );
 
-                //OPPONENT CARDS
-                Paragraph::new("Opponent Cards")
-                    .alignment(Alignment::Center)
-                    .block(block.clone())
-                    .render(top_game_layout[1], buf);
-
                 //RENDER PLAYER CARDS
 
                 let player_cards_layout = Layout::default()
                     .direction(Direction::Horizontal)
                     .constraints::<&Vec<Constraint>>(
-                        (0..self.player_cards.len())
-                            .map(|_| Constraint::Percentage(100 / self.player_cards.len() as u16))
+                        (0..self.game.hand(0).len())
+                            .map(|_| Constraint::Percentage(100 / self.game.hand(0).len() as u16))
                             .collect::<Vec<Constraint>>()
                             .as_ref(),
                     )
                     .split(game_layout[2]);
 
-                for (i, card) in self.player_cards.iter().enumerate() {
+                let playable_cards = self.game.playable_cards(0);
+                for (i, card) in self.game.hand(0).iter().enumerate() {
                     let card_canvas = Canvas::default().paint(|ctx| {
                         ctx.draw(&Rectangle {
                             x: 0.0,
@@ -604,20 +932,15 @@ impl Widget for &App {
                                 .position(Position::Bottom)
                                 .alignment(Alignment::Center),
                         );
-                    if let Some(slctd) = self.selected_card {
-                        let i = i as u8;
-                        if slctd == i {
-                            user_card_block = Block::bordered()
-                                .on_green()
-                                .title(
-                                    Title::from((i + 1).to_string())
-                                        .position(Position::Bottom)
-                                        .alignment(Alignment::Center),
-                                )
-                                .border_set(border::DOUBLE);
-                        }
-                    }
 
+                    if !playable_cards.contains(&i) {
+                        user_card_block = user_card_block.on_dark_gray().dim();
+                    }
+                    if self.game.revealed().contains(card) {
+                        user_card_block = user_card_block.title(
+                            Title::from("cante".yellow().bold()).alignment(Alignment::Center),
+                        );
+                    }
                     Paragraph::new(card_text)
                         .alignment(Alignment::Center)
                         .block(user_card_block)
@@ -625,10 +948,38 @@ impl Widget for &App {
                     card_canvas.render(card_area, buf);
                 }
 
+                //SIDE HANDS AROUND THE TABLE
+                let middle_layout = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Length(SIDE_HAND_WIDTH),
+                        Constraint::Min(0),
+                        Constraint::Length(SIDE_HAND_WIDTH),
+                    ])
+                    .split(game_layout[1]);
+                let mut table_area = game_layout[1];
+                for (position, area) in [
+                    (SeatPosition::Left, middle_layout[0]),
+                    (SeatPosition::Right, middle_layout[2]),
+                ] {
+                    if let Some(seat) = self.seats.seat_at(position) {
+                        self.render_side_hand(seat, area, buf);
+                        table_area = middle_layout[1];
+                    }
+                }
+
+                let mut table_block = block.clone();
+                if let Some(message) = &self.message {
+                    table_block = table_block.title(
+                        Title::from(format!(" {} ", message).yellow().bold())
+                            .alignment(Alignment::Center)
+                            .position(Position::Bottom),
+                    );
+                }
                 Paragraph::new("Table where the game is being played")
                     .alignment(Alignment::Center)
-                    .block(block.clone())
-                    .render(game_layout[1], buf);
+                    .block(table_block)
+                    .render(table_area, buf);
                 Paragraph::new("Your Cards")
                     .alignment(Alignment::Center)
                     .block(block.clone())
@@ -637,123 +988,176 @@ impl Widget for &App {
 
                 //RENDER TABLE LIKE MIDDLE PART
 
-                let layout_middle_vertically_divided_opponent_cards = Layout::default()
+                let table_rows = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(20),
-                        Constraint::Percentage(50),
+                        Constraint::Percentage(15),
+                        Constraint::Length(4),
+                        Constraint::Min(0),
                     ])
-                    .split(game_layout[1]);
-                let constraint_for_opponent_card =
-                    layout_middle_vertically_divided_opponent_cards[1];
-
-                if let Some(lst) = &self.last_played_opponent_card {
-                    let card_area = center(
-                        constraint_for_opponent_card,
-                        Constraint::Length(CARD_WIDTH),
-                        Constraint::Length(CARD_HEIGHT),
-                    );
-                    let crd_blck = Block::bordered()
-                        .border_set(border::PROPORTIONAL_TALL)
-                        .on_red();
-
-                    Paragraph::new(Line::from(lst.name()))
-                        .block(crd_blck)
-                        .render(card_area, buf)
-                }
-
-                //render own cards
-                let layout_own_card_on_board = Layout::default()
+                    .split(table_area);
+                let own_table_rows = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
+                        Constraint::Percentage(55),
+                        Constraint::Length(4),
+                        Constraint::Min(0),
+                    ])
+                    .split(table_area);
+                let side_table_rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Percentage(35),
+                        Constraint::Length(4),
+                        Constraint::Min(0),
+                    ])
+                    .split(table_area);
+                let side_table_columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Percentage(25),
                         Constraint::Percentage(50),
-                        Constraint::Percentage(20),
-                        Constraint::Percentage(30),
+                        Constraint::Percentage(25),
                     ])
-                    .split(game_layout[1]);
-                let constraint_for_card_board = layout_own_card_on_board[1];
-
-                if let Some(last) = &self.last_played_card {
-                    let card_area = center(
-                        constraint_for_card_board,
-                        Constraint::Length(CARD_WIDTH),
-                        Constraint::Length(CARD_HEIGHT),
-                    );
-                    let crd_blck = Block::bordered()
-                        .border_set(border::PROPORTIONAL_TALL)
-                        .on_red();
-
-                    Paragraph::new(Line::from(last.name()))
+                    .split(side_table_rows[1]);
+
+                for seat in 0..self.seats.players() {
+                    let slot = match self.seats.position(seat) {
+                        SeatPosition::Top => table_rows[1],
+                        SeatPosition::Bottom => own_table_rows[1],
+                        SeatPosition::Left => side_table_columns[0],
+                        SeatPosition::Right => side_table_columns[2],
+                    };
+                    if let Some(card) = self.game.table_card(seat) {
+                        let card_area = center(
+                            slot,
+                            Constraint::Length(CARD_WIDTH),
+                            Constraint::Length(CARD_HEIGHT),
+                        );
+                        let crd_blck = Block::bordered()
+                            .border_set(border::PROPORTIONAL_TALL)
+                            .on_red();
+
+                        Paragraph::new(vec![
+                            Line::from(card.name()),
+                            Line::from(card.emoji().to_string()),
+                        ])
                         .block(crd_blck)
                         .render(card_area, buf)
-                }
-
-                if (self.selected_card.is_some() && self.opponent_selected_card.is_some()) {
-                    match self.selected_card {
-                        Some(selected) => match self.opponent_selected_card {
-                            Some(opponent_selected) => {
-                                let selected_card = &self.player_cards[selected as usize];
-                                let opponent_selected_card =
-                                    &self.opponent_cards[opponent_selected as usize];
-                                let does_player_win =
-                                    self.do_x_defeat_y(&selected_card, &opponent_selected_card);
-
-                                if does_player_win {
-                                    //   self.set_screen(Screens::Win)
-                                } else {
-                                    //   self.set_screen(Screens::OpponentWin)
-                                }
-
-                                //self.clean_selected();
-                            }
-                            None => {}
-                        },
-                        None => {}
                     }
                 }
             }
-            Screens::Win => {
-                let text = vec![
-                    Line::from("Congratulations! Player 1, You have won the game"),
-                    Line::from("Press 'q' to quit the game"),
-                ];
-                let text = Text::from(text);
-                Paragraph::new(text)
-                    .alignment(Alignment::Center)
-                    .block(Block::default().borders(Borders::ALL))
-                    .render(area, buf);
-            }
-            Screens::OpponentWin => {
-                let area = center(area, Constraint::Percentage(50), Constraint::Percentage(50));
-                let text = vec![
-                    Line::from("Player 2, you have won the game! Congratulations!"),
-                    Line::from(
-                        "Player 1, you have lost (((the game)))!!!!!!!!!! You better practive more!",
-                    ),
-                    Line::from("Press 'q' to quit the game"),
-                    Line::styled(
-                        ":(((((((((((((((((((((((((((((((((",
-                        Style::new()
-                            .fg(Color::Red)
-                            .add_modifier(Modifier::RAPID_BLINK),
-                    ),
-
-                ];
-                let text = Text::from(text);
-                Paragraph::new("\n \n ").render(area, buf);
-                Paragraph::new(text)
-                    .alignment(Alignment::Center)
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .on_magenta()
-                            .padding(Padding::uniform(4)),
+            Screens::GameOver => match (self.game.phase(), self.game.tute()) {
+                (_, Some((seat, figure))) => {
+                    let figures = match figure {
+                        CardsValues::Rey => "Reyes",
+                        _ => "Caballos",
+                    };
+                    let cards = self
+                        .game
+                        .revealed()
+                        .iter()
+                        .filter(|card| card.value == figure)
+                        .map(|card| format!("{} {}", card.name(), card.emoji()))
+                        .collect::<Vec<String>>();
+                    let winner = if self.seats.side(seat) == self.seats.side(0) {
+                        "You win the game!".green().bold()
+                    } else {
+                        "You lose the game!".red().bold()
+                    };
+                    let area = center(area, Constraint::Percentage(60), Constraint::Length(10));
+                    let mut text = vec![
+                        Line::from(format!("Tute de {}!", figures)).yellow().bold(),
+                        Line::from(""),
+                        Line::from(format!(
+                            "{} sang the four {}",
+                            self.seats.name(seat),
+                            figures
+                        )),
+                        Line::from(cards.join("  ")),
+                        Line::from(""),
+                        Line::from(winner),
+                    ];
+                    text.extend(self.match_progress());
+                    Paragraph::new(text)
+                        .alignment(Alignment::Center)
+                        .block(Block::bordered().border_set(border::DOUBLE))
+                        .render(area, buf);
+                }
+                (GamePhase::GameOver { side }, _) if side == self.seats.side(0) => {
+                    let mut text = vec![
+                        Line::from(format!(
+                            "Congratulations! {} won the game",
+                            self.side_name(side)
+                        )),
+                        Line::from(format!("Final score {}", self.final_score())),
+                    ];
+                    text.extend(self.match_progress());
+                    let text = Text::from(text);
+                    Paragraph::new(text)
+                        .alignment(Alignment::Center)
+                        .block(Block::default().borders(Borders::ALL))
+                        .render(area, buf);
+                }
+                (GamePhase::GameOver { side }, _) => {
+                    let area = center(area, Constraint::Percentage(50), Constraint::Percentage(50));
+                    let mut text = vec![
+                        Line::from(format!("{} won the game!", self.side_name(side))),
+                        Line::from(format!(
+                            "{} lost the game! You better practice more!",
+                            self.side_name(self.seats.side(0))
+                        )),
+                        Line::from(format!("Final score {}", self.final_score())),
+                        Line::styled(
+                            ":(((((((((((((((((((((((((((((((((",
+                            Style::new()
+                                .fg(Color::Red)
+                                .add_modifier(Modifier::RAPID_BLINK),
+                        ),
+                    ];
+                    text.extend(self.match_progress());
+                    let text = Text::from(text);
+                    Paragraph::new("\n \n ").render(area, buf);
+                    Paragraph::new(text)
+                        .alignment(Alignment::Center)
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .on_magenta()
+                                .padding(Padding::uniform(4)),
+                        )
+                        .render(area, buf);
+                    Paragraph::new(crown)
+                        .alignment(Alignment::Center)
+                        .render(area, buf);
+                }
+                _ => {}
+            },
+            Screens::HandReview => {
+                let instructions = Title::from(Line::from(vec![
+                    " Continue ".into(),
+                    "<Enter> ".blue().bold(),
+                ]));
+                let block = Block::bordered()
+                    .title(Title::from(" Bazas of the hand ".bold()).alignment(Alignment::Center))
+                    .title(
+                        instructions
+                            .alignment(Alignment::Center)
+                            .position(Position::Bottom),
                     )
-                    .render(area, buf);
-                Paragraph::new(crown)
-                    .alignment(Alignment::Center)
-                    .render(area, buf);
+                    .border_set(border::THICK);
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(
+                        (0..self.seats.sides())
+                            .map(|_| Constraint::Ratio(1, self.seats.sides() as u32))
+                            .collect::<Vec<Constraint>>(),
+                    )
+                    .split(block.inner(area));
+                block.render(area, buf);
+                for side in 0..self.seats.sides() {
+                    self.render_baza(side, columns[side], buf);
+                }
             }
             _ => {}
         }
@@ -761,12 +1165,158 @@ impl Widget for &App {
 }
 
 fn main() -> io::Result<()> {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    });
+    let mut app = match options.seed {
+        Some(seed) => App::with_seed(seed),
+        None => App::new(),
+    };
+    if let (Some(seed), Some(Budget::Time(_))) = (options.seed, options.budget) {
+        eprintln!(
+            "warning: with a budget in time the seed {} may not play the same games again",
+            seed
+        );
+    }
+    if let Some(budget) = options.budget {
+        //asking for a budget is asking to play the expert
+        app.budget = budget;
+        app.difficulty = Difficulty::Expert(budget);
+    }
+    for (seat, mut sitter) in options.sitters {
+        if let (Sitter::Computer(Difficulty::Expert(_)), Some(budget)) = (&sitter, options.budget) {
+            sitter = Sitter::Computer(Difficulty::Expert(budget));
+        }
+        if app.sitters.len() <= seat {
+            app.sitters.resize(seat + 1, None);
+        }
+        app.sitters[seat] = Some(sitter);
+    }
     let mut terminal = game::init()?;
-    let app_result = App::new().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     game::restore()?;
     app_result
 }
 
+/// The options given in the command line
+#[derive(Debug, Default)]
+struct Options {
+    seed: Option<u64>,
+    budget: Option<Budget>,
+    //who sits at the seats that are not the default
+    sitters: Vec<(usize, Sitter)>,
+}
+
+/// The `--seed <number>`, `--budget <iterations or time>` and `--seat <seat>=<player>`
+/// options, also given as `--seed=<number>`. The same seed only plays the same games with a
+/// budget in iterations, the expert thinks further in the same time on a faster computer
+fn parse_args(mut args: impl Iterator<Item = String>) -> io::Result<Options> {
+    let invalid = |message: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{}\nusage: guinotecli [--seed <number>] [--budget <iterations>|<ms>ms|<s>s] \
+                 [--seat <0-3>=you|random|heuristic|search|expert|script:<file>]...",
+                message
+            ),
+        )
+    };
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None if ["--seed", "--budget", "--seat"].contains(&arg.as_str()) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid(format!("{} needs a value", arg)))?;
+                (arg, value)
+            }
+            None => (arg, String::new()),
+        };
+        match name.as_str() {
+            "--seed" => {
+                let seed = value
+                    .parse::<u64>()
+                    .map_err(|_| invalid(format!("the seed must be a number, not {}", value)))?;
+                options.seed = Some(seed);
+            }
+            "--budget" => {
+                let budget = value
+                    .parse::<Budget>()
+                    .map_err(|error| invalid(error.to_string()))?;
+                options.budget = Some(budget);
+            }
+            "--seat" => {
+                let sitter = value
+                    .split_once('=')
+                    .ok_or_else(|| {
+                        format!("the seat must be given as <seat>=<player>, not {}", value)
+                    })
+                    .and_then(|(seat, player)| parse_sitter(seat, player))
+                    .map_err(invalid)?;
+                options.sitters.push(sitter);
+            }
+            _ => return Err(invalid(format!("unknown argument {}", name))),
+        }
+    }
+    Ok(options)
+}
+
+/// A seat and who sits at it, "2=expert" or "1=script:moves.txt"
+fn parse_sitter(seat: &str, player: &str) -> Result<(usize, Sitter), String> {
+    let seat = seat
+        .parse::<usize>()
+        .ok()
+        .filter(|seat| *seat < 4)
+        .ok_or_else(|| format!("the seat must be a number from 0 to 3, not {}", seat))?;
+    let sitter = match player.split_once(':') {
+        Some(("script", path)) => {
+            let text = fs::read_to_string(path)
+                .map_err(|error| format!("the script {} can not be read: {}", path, error))?;
+            let script = text
+                .parse::<Scripted>()
+                .map_err(|error| format!("the script {} is not valid: {}", path, error))?;
+            Sitter::Script(script)
+        }
+        _ if player.eq_ignore_ascii_case("you") => Sitter::You,
+        _ => Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(player))
+            .map(Sitter::Computer)
+            .ok_or_else(|| format!("unknown player {}", player))?,
+    };
+    //there is one keyboard and the table is seen from the first seat
+    if matches!(sitter, Sitter::You) && seat != 0 {
+        return Err(format!("you can only sit at seat 0, not {}", seat));
+    }
+    Ok((seat, sitter))
+}
+
+fn rules_summary(rules: &RuleSet) -> String {
+    let yes_no = |rule: bool| if rule { "yes" } else { "no" };
+    format!(
+        "{} points, cantes need a trick: {}, exchange the Dos: {}, must overtrump: {}, capote: +{}, tute: {}",
+        rules.winning_points,
+        yes_no(rules.cante_requires_trick),
+        yes_no(rules.dos_exchange),
+        yes_no(rules.must_overtrump),
+        rules.capote_bonus,
+        yes_no(rules.tute)
+    )
+}
+
+fn difficulty_summary(difficulty: Difficulty, stats: &Stats) -> String {
+    let description = match difficulty {
+        Difficulty::Random => "plays any legal card",
+        Difficulty::Heuristic => "saves triunfos, takes cheap points and sings its cantes",
+        Difficulty::Search => "tries every card against guesses of your hand",
+        Difficulty::Expert(_) => "searches the plays of everyone against guesses of your hand",
+    };
+    let (won, played) = stats.results(difficulty);
+    format!("{}, you won {} of {} games", description, won, played)
+}
+
 fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::horizontal([horizontal])
         .flex(Flex::Center)
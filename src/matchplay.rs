@@ -0,0 +1,40 @@
+/// Games won to take a coto
+pub const GAMES_PER_COTO: u8 = 2;
+
+/// The running score of a match. Every `GAMES_PER_COTO` games won by a side make a coto, and
+/// the first side to win `cotos_to_win` cotos takes the match
+#[derive(Debug, Clone)]
+pub struct MatchScore {
+    pub cotos_to_win: u8,
+    //games won by each side in the coto being played
+    pub games: Vec<u8>,
+    pub cotos: Vec<u8>,
+}
+
+impl MatchScore {
+    pub fn new(sides: usize, cotos_to_win: u8) -> Self {
+        MatchScore {
+            cotos_to_win,
+            games: vec![0; sides],
+            cotos: vec![0; sides],
+        }
+    }
+
+    pub fn record_game(&mut self, winner: usize) {
+        self.games[winner] += 1;
+        if self.games[winner] == GAMES_PER_COTO {
+            self.cotos[winner] += 1;
+            self.games.iter_mut().for_each(|games| *games = 0);
+        }
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.cotos
+            .iter()
+            .position(|&cotos| cotos >= self.cotos_to_win)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+}
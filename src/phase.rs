@@ -0,0 +1,87 @@
+use std::fmt;
+
+use crate::rules::Phase;
+
+/// Where a game is at. It only moves along the transitions of `GamePhase::can_go_to`, the
+/// dealing, scoring and vueltas phases are passed through while an action is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+    Dealing,
+    //playing tricks while the talon has cards
+    Draw,
+    Arrastre,
+    //the seat that won the last trick, its side may sing or exchange the siete until it leads
+    CanteWindow { seat: usize },
+    //the last trick has been played and the hand is being scored
+    Scoring,
+    //nobody reached the winning points, another hand is about to be dealt
+    Vueltas,
+    GameOver { side: usize },
+}
+
+impl GamePhase {
+    /// The phase of playing tricks, depending on whether the talon has run out
+    pub fn playing(phase: Phase) -> Self {
+        match phase {
+            Phase::Draw => GamePhase::Draw,
+            Phase::Arrastre => GamePhase::Arrastre,
+        }
+    }
+
+    pub fn can_go_to(&self, to: &GamePhase) -> bool {
+        use GamePhase::*;
+        matches!(
+            (self, to),
+            (Dealing, Draw)
+                | (
+                    Draw | Arrastre,
+                    CanteWindow { .. } | Scoring | GameOver { .. }
+                )
+                | (CanteWindow { .. }, Draw | Arrastre | GameOver { .. })
+                | (Scoring, Vueltas | GameOver { .. })
+                | (Vueltas, Dealing)
+        )
+    }
+
+    /// Moves to `to`, or fails leaving the phase as it was if there is no such transition
+    pub fn transition(&mut self, to: GamePhase) -> Result<(), InvalidTransition> {
+        if !self.can_go_to(&to) {
+            return Err(InvalidTransition { from: *self, to });
+        }
+        *self = to;
+        Ok(())
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self, GamePhase::GameOver { .. })
+    }
+}
+
+impl fmt::Display for GamePhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GamePhase::Dealing => "Dealing",
+            GamePhase::Draw => "Drawing",
+            GamePhase::Arrastre => "Arrastre",
+            GamePhase::CanteWindow { .. } => "Cante window",
+            GamePhase::Scoring => "Scoring",
+            GamePhase::Vueltas => "Vueltas",
+            GamePhase::GameOver { .. } => "Game over",
+        })
+    }
+}
+
+/// A move between two phases that the game does not allow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub from: GamePhase,
+    pub to: GamePhase,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The game can not go from {} to {}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
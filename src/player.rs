@@ -0,0 +1,393 @@
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+use crate::{
+    ai::{Computer, Difficulty},
+    cante::Cante,
+    deck::Talon,
+    phase::GamePhase,
+    ruleset::RuleSet,
+    scoring::HandScore,
+    seats::Seats,
+    trick::Trick,
+    Action, Card, CardsValues, Events, GameEvent, GameState, Palos, ParseCardError,
+};
+
+/// Someone sitting at a seat: the local player at the keyboard, the computer, a script, or
+/// anyone else able to choose an action from what the seat can see
+pub trait Player: fmt::Debug {
+    /// The action to take now, or none to wait for the other seats or for some input
+    fn act(&mut self, observation: &Observation) -> Option<Action>;
+
+    /// Gives the player an action chosen by its user, the players that choose on their own
+    /// ignore it
+    fn input(&mut self, _action: Action) {}
+}
+
+/// What a seat can see of a game: its own hand, how many cards everyone else holds, the
+/// table, the face up triunfo card and the cards shown singing, the scores and the log of
+/// what everyone saw happen. It owns all of it, so it can be kept or sent elsewhere
+#[derive(Debug, Clone)]
+pub struct Observation {
+    seat: usize,
+    seats: Seats,
+    rules: RuleSet,
+    dealer: usize,
+    cut_deck: bool,
+    hand: Vec<Card>,
+    hand_sizes: Vec<usize>,
+    points: Vec<u16>,
+    hand_score: HandScore,
+    vueltas: bool,
+    triunfo: Palos,
+    triunfo_card: Option<Card>,
+    talon_remaining: usize,
+    trick: Trick,
+    last_trick: Option<Trick>,
+    phase: GamePhase,
+    sung: Vec<Palos>,
+    revealed: Vec<Card>,
+    tute: Option<(usize, CardsValues)>,
+    //what the rules let the seat do with its hand now
+    playable_cards: Vec<usize>,
+    available_cantes: Vec<Cante>,
+    tute_figure: Option<CardsValues>,
+    exchangeable_card: Option<usize>,
+    log: Events,
+}
+
+impl Observation {
+    /// What `seat` sees of `game` now
+    pub fn new(game: &GameState, seat: usize) -> Self {
+        Observation {
+            seat,
+            seats: game.seats(),
+            rules: *game.rules(),
+            dealer: game.dealer(),
+            cut_deck: game.cut_deck(),
+            hand: game.hand(seat).to_vec(),
+            hand_sizes: (0..game.seats().players())
+                .map(|other| game.hand(other).len())
+                .collect(),
+            points: game.points().to_vec(),
+            hand_score: game.hand_score().clone(),
+            vueltas: game.is_vueltas(),
+            triunfo: game.triunfo(),
+            triunfo_card: game.talon().triunfo_card().copied(),
+            talon_remaining: game.talon().remaining(),
+            trick: game.trick().clone(),
+            last_trick: game.last_trick().cloned(),
+            phase: game.phase(),
+            sung: game.sung().to_vec(),
+            revealed: game.revealed().to_vec(),
+            tute: game.tute(),
+            playable_cards: game.playable_cards(seat),
+            available_cantes: game.available_cantes(seat),
+            tute_figure: game.tute_figure(seat),
+            exchangeable_card: game.exchangeable_card(seat),
+            log: game
+                .log()
+                .iter()
+                .map(|event| seen_by(event, seat))
+                .collect(),
+        }
+    }
+
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    pub fn seats(&self) -> Seats {
+        self.seats
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    pub fn cut_deck(&self) -> bool {
+        self.cut_deck
+    }
+
+    pub fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+
+    /// The number of cards in the hand of `seat`
+    pub fn hand_size(&self, seat: usize) -> usize {
+        self.hand_sizes[seat]
+    }
+
+    /// The points of each side in the previous hands
+    pub fn points(&self) -> &[u16] {
+        &self.points
+    }
+
+    /// The points of `side` including the hand being played
+    pub fn score(&self, side: usize) -> u16 {
+        self.points[side] + self.hand_score.total(side)
+    }
+
+    /// The tricks and cantes of each side in the hand being played
+    pub fn hand_score(&self) -> &HandScore {
+        &self.hand_score
+    }
+
+    pub fn is_vueltas(&self) -> bool {
+        self.vueltas
+    }
+
+    pub fn triunfo(&self) -> Palos {
+        self.triunfo
+    }
+
+    pub fn triunfo_card(&self) -> Option<&Card> {
+        self.triunfo_card.as_ref()
+    }
+
+    /// The cards left in the talon, the face up one included
+    pub fn talon_remaining(&self) -> usize {
+        self.talon_remaining
+    }
+
+    pub fn trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    pub fn last_trick(&self) -> Option<&Trick> {
+        self.last_trick.as_ref()
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    pub fn turn(&self) -> usize {
+        (self.trick.leader() + self.trick.len()) % self.seats.players()
+    }
+
+    pub fn sung(&self) -> &[Palos] {
+        &self.sung
+    }
+
+    pub fn revealed(&self) -> &[Card] {
+        &self.revealed
+    }
+
+    pub fn tute(&self) -> Option<(usize, CardsValues)> {
+        self.tute
+    }
+
+    pub fn playable_cards(&self) -> &[usize] {
+        &self.playable_cards
+    }
+
+    pub fn available_cantes(&self) -> &[Cante] {
+        &self.available_cantes
+    }
+
+    pub fn tute_figure(&self) -> Option<CardsValues> {
+        self.tute_figure
+    }
+
+    pub fn exchangeable_card(&self) -> Option<usize> {
+        self.exchangeable_card
+    }
+
+    /// The events of the game as the seat saw them. The seed is left out, and the deals only
+    /// show its own hand and the face up card
+    pub fn log(&self) -> &[GameEvent] {
+        &self.log
+    }
+}
+
+fn seen_by(event: &GameEvent, seat: usize) -> GameEvent {
+    match event {
+        GameEvent::GameStarted {
+            seats,
+            rules,
+            cut_deck,
+            ..
+        } => GameEvent::GameStarted {
+            seats: *seats,
+            rules: *rules,
+            cut_deck: *cut_deck,
+            seed: 0,
+        },
+        GameEvent::Dealt {
+            dealer,
+            hands,
+            talon,
+        } => {
+            let mut face_up = Talon::default();
+            if let Some(card) = talon.triunfo_card() {
+                face_up.exchange_triunfo_card(*card);
+            }
+            GameEvent::Dealt {
+                dealer: *dealer,
+                hands: hands
+                    .iter()
+                    .enumerate()
+                    .map(|(other, hand)| {
+                        if other == seat {
+                            hand.clone()
+                        } else {
+                            Vec::new()
+                        }
+                    })
+                    .collect(),
+                talon: face_up,
+            }
+        }
+        event => event.clone(),
+    }
+}
+
+/// A player that follows a script of cards and sayings, and plays like the heuristic once it
+/// runs out of it or its next card is not in the hand
+#[derive(Debug, Clone, Default)]
+pub struct Scripted {
+    steps: VecDeque<Step>,
+}
+
+/// Something a script says to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Play(Card),
+    Sing,
+    SingTute,
+    Exchange,
+}
+
+impl FromStr for Step {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "sing" => Ok(Step::Sing),
+            "tute" => Ok(Step::SingTute),
+            "exchange" => Ok(Step::Exchange),
+            _ => text.parse().map(Step::Play),
+        }
+    }
+}
+
+/// Parses the steps separated by spaces, commas or lines, the cards in their short notation:
+/// "AO 3E sing RC"
+impl FromStr for Scripted {
+    type Err = ParseCardError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let steps = text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|step| !step.is_empty())
+            .map(str::parse)
+            .collect::<Result<VecDeque<Step>, ParseCardError>>()?;
+        Ok(Scripted { steps })
+    }
+}
+
+impl Player for Scripted {
+    fn act(&mut self, observation: &Observation) -> Option<Action> {
+        let seat = observation.seat();
+        let my_turn = observation.turn() == seat && !observation.phase().is_over();
+        let action = match self.steps.front() {
+            None => return heuristic(observation),
+            Some(Step::Play(card)) if my_turn => {
+                match observation.hand().iter().position(|held| held == card) {
+                    Some(card) => Action::Play { seat, card },
+                    None => {
+                        self.steps.clear();
+                        return heuristic(observation);
+                    }
+                }
+            }
+            Some(Step::Sing) if !observation.available_cantes().is_empty() => Action::Sing { seat },
+            Some(Step::SingTute) if observation.tute_figure().is_some() => {
+                Action::SingTute { seat }
+            }
+            Some(Step::Exchange) if observation.exchangeable_card().is_some() => {
+                Action::Exchange { seat }
+            }
+            //a saying that can not be done on its turn is left out
+            Some(Step::Sing | Step::SingTute | Step::Exchange) if my_turn => {
+                self.steps.pop_front();
+                return self.act(observation);
+            }
+            Some(_) => return None,
+        };
+        self.steps.pop_front();
+        Some(action)
+    }
+}
+
+fn heuristic(observation: &Observation) -> Option<Action> {
+    //the heuristic plays the same whatever it guesses of the cards it can not see
+    Computer::new(Difficulty::Heuristic, 0).act(observation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observes_only_its_own_hand() {
+        let game = GameState::new(Seats::new(4), RuleSet::CLASICO, 0, true, 4);
+        let observation = Observation::new(&game, 2);
+        assert_eq!(observation.hand(), game.hand(2));
+        assert!((0..4).all(|seat| observation.hand_size(seat) == game.hand(seat).len()));
+        for event in observation.log() {
+            match event {
+                GameEvent::GameStarted { seed, .. } => assert_eq!(*seed, 0),
+                GameEvent::Dealt { hands, talon, .. } => {
+                    assert_eq!(hands[2], game.hand(2));
+                    assert!([0, 1, 3].iter().all(|&seat| hands[seat].is_empty()));
+                    assert_eq!(talon.remaining(), 1);
+                    assert_eq!(talon.triunfo_card(), game.talon().triunfo_card());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn parses_scripts() {
+        let script = "AO, 3E\nsing  tute exchange".parse::<Scripted>().unwrap();
+        assert_eq!(
+            Vec::from(script.steps),
+            [
+                Step::Play("AO".parse().unwrap()),
+                Step::Play("3E".parse().unwrap()),
+                Step::Sing,
+                Step::SingTute,
+                Step::Exchange,
+            ]
+        );
+        assert_eq!(
+            "AO XX".parse::<Scripted>().unwrap_err(),
+            ParseCardError::UnknownValue("X".to_string())
+        );
+    }
+
+    #[test]
+    fn plays_its_script() {
+        let game = GameState::new(Seats::new(2), RuleSet::CLASICO, 0, true, 6);
+        let seat = game.turn();
+        let card = game.hand(seat)[3];
+        let mut script = card.to_string().parse::<Scripted>().unwrap();
+        let observation = Observation::new(&game, seat);
+        assert_eq!(
+            script.act(&observation),
+            Some(Action::Play { seat, card: 3 })
+        );
+        //once the script runs out it plays like the heuristic
+        assert_eq!(
+            script.act(&observation),
+            crate::ai::heuristic_action(&game, seat)
+        );
+        assert_eq!(script.act(&Observation::new(&game, 1 - seat)), None);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{ruleset::RuleSet, trick::Trick, Card, CardsValues, Palos};
+
+/// While the talon has cards any card can be played, once it runs out the arrastre starts
+/// and the follow suit rules apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Draw,
+    Arrastre,
+}
+
+/// Returns the indexes of the cards of `hand` that can be played in the current `trick`.
+/// When the trick is being won by a `partner` there is no need to beat it
+pub fn legal_moves(
+    hand: &[Card],
+    trick: &Trick,
+    triunfo: Palos,
+    phase: Phase,
+    partner_winning: bool,
+    rules: &RuleSet,
+) -> Vec<usize> {
+    let all = (0..hand.len()).collect::<Vec<usize>>();
+    let (Some(led_palo), Some((_, winning))) = (trick.led_palo(), trick.winning(triunfo)) else {
+        return all;
+    };
+    if phase == Phase::Draw {
+        return all;
+    }
+
+    let filter = |predicate: &dyn Fn(&Card) -> bool| -> Vec<usize> {
+        all.iter()
+            .copied()
+            .filter(|&i| predicate(&hand[i]))
+            .collect()
+    };
+    let beats = |card: &Card| crate::trick::do_x_defeat_y(card, winning, triunfo);
+
+    //follow suit, beating the winning card if possible
+    let same_palo = filter(&|card| card.palo == led_palo);
+    if partner_winning {
+        return if same_palo.is_empty() { all } else { same_palo };
+    }
+    if !same_palo.is_empty() {
+        let beating = filter(&|card| card.palo == led_palo && beats(card));
+        return if beating.is_empty() {
+            same_palo
+        } else {
+            beating
+        };
+    }
+
+    //can't follow suit, must trump, over the winning card if the rules say so
+    let triunfos = filter(&|card| card.palo == triunfo && (beats(card) || !rules.must_overtrump));
+    if !triunfos.is_empty() {
+        return triunfos;
+    }
+
+    all
+}
+
+/// Explains why the card at `index` can't be played, if it can't
+pub fn illegal_move_reason(
+    hand: &[Card],
+    index: usize,
+    trick: &Trick,
+    triunfo: Palos,
+    phase: Phase,
+    partner_winning: bool,
+    rules: &RuleSet,
+) -> Option<String> {
+    if legal_moves(hand, trick, triunfo, phase, partner_winning, rules).contains(&index) {
+        return None;
+    }
+    let led_palo = trick.led_palo()?;
+    let (_, winning) = trick.winning(triunfo)?;
+    let card = &hand[index];
+    Some(if hand.iter().any(|c| c.palo == led_palo) {
+        if card.palo == led_palo {
+            format!("Must beat the {} of {}", winning.name(), winning.palo)
+        } else {
+            format!("Must follow suit, {} was led", led_palo)
+        }
+    } else if rules.must_overtrump {
+        format!("Must play a winning triunfo, {}", triunfo)
+    } else {
+        format!("Must play a triunfo, {}", triunfo)
+    })
+}
+
+/// The card of `hand` that can be exchanged for the face up `triunfo_card`, the Siete of
+/// triunfo or, if `dos_exchange` is allowed, the Dos when the face up card is a figure or the Siete
+pub fn exchange_card(hand: &[Card], triunfo_card: &Card, dos_exchange: bool) -> Option<usize> {
+    let find = |value: CardsValues| {
+        hand.iter().position(|card| {
+            card.value == value && card.palo == triunfo_card.palo && card != triunfo_card
+        })
+    };
+    let dos_allowed = dos_exchange
+        && matches!(
+            triunfo_card.value,
+            CardsValues::Sota | CardsValues::Caballo | CardsValues::Rey | CardsValues::Siete
+        );
+    find(CardsValues::Siete).or_else(|| dos_allowed.then(|| find(CardsValues::Dos)).flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(text: &str) -> Vec<Card> {
+        text.split_whitespace()
+            .map(|card| card.parse().unwrap())
+            .collect()
+    }
+
+    fn trick(text: &str) -> Trick {
+        let mut trick = Trick::new(0);
+        for (seat, card) in cards(text).into_iter().enumerate() {
+            trick.play(seat, card);
+        }
+        trick
+    }
+
+    fn legal(hand: &str, played: &str, partner_winning: bool, rules: &RuleSet) -> Vec<usize> {
+        let hand = cards(hand);
+        let trick = trick(played);
+        legal_moves(
+            &hand,
+            &trick,
+            Palos::Bastos,
+            Phase::Arrastre,
+            partner_winning,
+            rules,
+        )
+    }
+
+    #[test]
+    fn anything_goes_while_drawing() {
+        let hand = cards("AO 4O RC 2B");
+        let trick = trick("3O");
+        let moves = legal_moves(
+            &hand,
+            &trick,
+            Palos::Bastos,
+            Phase::Draw,
+            false,
+            &RuleSet::CLASICO,
+        );
+        assert_eq!(moves, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn leading_anything_goes() {
+        assert_eq!(
+            legal("AO 4O RC 2B", "", false, &RuleSet::CLASICO),
+            [0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn must_follow_suit_and_beat() {
+        let rules = RuleSet::CLASICO;
+        assert_eq!(legal("AO 4O RC 2B", "3O", false, &rules), [0]);
+        assert_eq!(legal("4O 5O RC 2B", "3O", false, &rules), [0, 1]);
+    }
+
+    #[test]
+    fn must_trump_without_the_palo() {
+        let rules = RuleSet::CLASICO;
+        assert_eq!(legal("RC 2B 5B", "3O", false, &rules), [1, 2]);
+        assert_eq!(legal("RC SE", "3O", false, &rules), [0, 1]);
+    }
+
+    #[test]
+    fn must_overtrump() {
+        //the Siete beats the triunfo played, the Dos does not
+        assert_eq!(legal("2B 7B RC", "3O 4B", false, &RuleSet::CLASICO), [1]);
+        //not being able to overtrump any triunfo can be played, or any card without it
+        assert_eq!(legal("2B RC", "3O 4B", false, &RuleSet::CLASICO), [0, 1]);
+    }
+
+    #[test]
+    fn overtrumping_is_a_house_rule() {
+        assert_eq!(legal("2B 7B RC", "3O 4B", false, &RuleSet::LARGO), [0, 1]);
+        let reason = illegal_move_reason(
+            &cards("2B RC"),
+            1,
+            &trick("3O 4B"),
+            Palos::Bastos,
+            Phase::Arrastre,
+            false,
+            &RuleSet::LARGO,
+        );
+        assert_eq!(
+            reason,
+            Some(format!("Must play a triunfo, {}", Palos::Bastos))
+        );
+    }
+
+    #[test]
+    fn partner_winning_only_follows_suit() {
+        let rules = RuleSet::CLASICO;
+        assert_eq!(legal("AO 4O RC 2B", "3O", true, &rules), [0, 1]);
+        assert_eq!(legal("RC 2B 5B", "3O", true, &rules), [0, 1, 2]);
+    }
+
+    #[test]
+    fn explains_illegal_moves() {
+        let reason = |hand: &str, index: usize| {
+            let hand = cards(hand);
+            illegal_move_reason(
+                &hand,
+                index,
+                &trick("3O"),
+                Palos::Bastos,
+                Phase::Arrastre,
+                false,
+                &RuleSet::CLASICO,
+            )
+        };
+        assert_eq!(reason("AO 4O", 0), None);
+        assert_eq!(
+            reason("AO 4O", 1),
+            Some(format!("Must beat the Tres of {}", Palos::Oros))
+        );
+        assert_eq!(
+            reason("AO RC", 1),
+            Some(format!("Must follow suit, {} was led", Palos::Oros))
+        );
+        assert_eq!(
+            reason("RC 2B", 0),
+            Some(format!("Must play a winning triunfo, {}", Palos::Bastos))
+        );
+    }
+}
@@ -0,0 +1,65 @@
+/// The house rules a table plays with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSet {
+    pub name: &'static str,
+    //cantes can only be sung after winning a trick, otherwise before leading
+    pub cante_requires_trick: bool,
+    pub winning_points: u16,
+    //the Dos of triunfo can be exchanged when the face up card is a figure or the Siete
+    pub dos_exchange: bool,
+    //in the arrastre a triunfo must beat the triunfos already played if it can
+    pub must_overtrump: bool,
+    //extra points for winning every trick of a hand, none if 0
+    pub capote_bonus: u16,
+    //singing the four Reyes or the four Caballos wins the game
+    pub tute: bool,
+}
+
+impl RuleSet {
+    pub const CLASICO: RuleSet = RuleSet {
+        name: "Clásico",
+        cante_requires_trick: true,
+        winning_points: 101,
+        dos_exchange: false,
+        must_overtrump: true,
+        capote_bonus: 0,
+        tute: true,
+    };
+
+    pub const CON_DOS: RuleSet = RuleSet {
+        name: "Con dos",
+        cante_requires_trick: true,
+        winning_points: 101,
+        dos_exchange: true,
+        must_overtrump: true,
+        capote_bonus: 50,
+        tute: true,
+    };
+
+    pub const LARGO: RuleSet = RuleSet {
+        name: "Largo",
+        cante_requires_trick: false,
+        winning_points: 151,
+        dos_exchange: true,
+        must_overtrump: false,
+        capote_bonus: 100,
+        tute: false,
+    };
+
+    pub const PRESETS: [RuleSet; 3] = [RuleSet::CLASICO, RuleSet::CON_DOS, RuleSet::LARGO];
+
+    /// The preset after this one, to cycle through them
+    pub fn next_preset(&self) -> RuleSet {
+        let index = RuleSet::PRESETS
+            .iter()
+            .position(|preset| preset == self)
+            .map_or(0, |index| (index + 1) % RuleSet::PRESETS.len());
+        RuleSet::PRESETS[index]
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet::CLASICO
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{ruleset::RuleSet, trick::Trick};
+
+/// Points for winning the last trick of a hand, the "diez de últimas"
+pub const ULTIMAS: u16 = 10;
+/// Points of all the cards of the deck plus the diez de últimas, every hand adds up to this
+pub const HAND_TOTAL: u16 = 130;
+
+/// The points each side has made during the current hand, indexed by side
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HandScore {
+    //the tricks captured by each side, their baza pile
+    pub bazas: Vec<Vec<Trick>>,
+    pub cantes: Vec<u16>,
+    //the side that won the last trick, once the hand is over
+    pub ultimas: Option<usize>,
+    //the side that won every trick and the bonus it gets for it, once the hand is over
+    pub capote: Option<(usize, u16)>,
+}
+
+impl HandScore {
+    pub fn new(sides: usize) -> Self {
+        HandScore {
+            bazas: vec![Vec::new(); sides],
+            cantes: vec![0; sides],
+            ultimas: None,
+            capote: None,
+        }
+    }
+
+    /// Puts a won trick in the baza of `side`
+    pub fn add_trick(&mut self, trick: Trick, side: usize) {
+        self.bazas[side].push(trick);
+    }
+
+    /// The points of the cards in the baza of `side`
+    pub fn card_points(&self, side: usize) -> u16 {
+        self.bazas[side].iter().map(Trick::points).sum()
+    }
+
+    pub fn add_cante(&mut self, points: u16, side: usize) {
+        self.cantes[side] += points;
+    }
+
+    /// Closes the hand giving the diez de últimas to `side`, and the capote bonus if they
+    /// won every trick
+    pub fn finish(&mut self, side: usize, rules: &RuleSet) {
+        self.ultimas = Some(side);
+        let tricks = self.bazas.iter().map(Vec::len).sum::<usize>();
+        if rules.capote_bonus > 0 && self.bazas[side].len() == tricks {
+            self.capote = Some((side, rules.capote_bonus));
+        }
+        debug_assert_eq!(
+            (0..self.bazas.len())
+                .map(|side| self.card_points(side))
+                .sum::<u16>()
+                + ULTIMAS,
+            HAND_TOTAL,
+            "the card points and the diez de últimas of a hand must add up to {}",
+            HAND_TOTAL
+        );
+    }
+
+    pub fn total(&self, side: usize) -> u16 {
+        let ultimas = if self.ultimas == Some(side) {
+            ULTIMAS
+        } else {
+            0
+        };
+        let capote = match self.capote {
+            Some((capote_side, bonus)) if capote_side == side => bonus,
+            _ => 0,
+        };
+        self.card_points(side) + self.cantes[side] + ultimas + capote
+    }
+}
+
+/// The side that wins the game with the given `scores`, if any reached the winning points.
+/// When several did, the side that won the diez de últimas takes the game
+pub fn game_winner(scores: &[u16], ultimas: Option<usize>, rules: &RuleSet) -> Option<usize> {
+    let winners = (0..scores.len())
+        .filter(|&side| scores[side] >= rules.winning_points)
+        .collect::<Vec<usize>>();
+    match winners[..] {
+        [] => None,
+        [side] => Some(side),
+        _ => ultimas
+            .filter(|side| winners.contains(side))
+            .or_else(|| winners.into_iter().max_by_key(|&side| scores[side])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai, deck::Deck, seats::Seats, Card, GameEvent, GameState};
+
+    #[test]
+    fn the_deck_and_ultimas_make_the_hand_total() {
+        for players in 2..=4 {
+            let cards = Deck::for_players(players)
+                .cards()
+                .iter()
+                .map(Card::value)
+                .map(u16::from)
+                .sum::<u16>();
+            assert_eq!(cards + ULTIMAS, HAND_TOTAL);
+        }
+    }
+
+    #[test]
+    fn every_hand_played_adds_up_to_the_total() {
+        for players in 2..=4 {
+            for (seed, rules) in RuleSet::PRESETS.into_iter().enumerate() {
+                let mut game = GameState::new(Seats::new(players), rules, 0, true, seed as u64);
+                ai::play_out_heuristic(&mut game, |_, _| true);
+                //a hand the vueltas end early is scored without its last tricks
+                let finished = game.log().iter().filter_map(|event| match event {
+                    GameEvent::HandScored { score, .. } if score.ultimas.is_some() => Some(score),
+                    _ => None,
+                });
+                let mut hands = 0;
+                for score in finished {
+                    let cards = (0..score.bazas.len())
+                        .map(|side| score.card_points(side))
+                        .sum::<u16>();
+                    assert_eq!(cards + ULTIMAS, HAND_TOTAL);
+                    hands += 1;
+                }
+                assert!(hands > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn ultimas_break_a_tie_of_winners() {
+        let rules = RuleSet::CLASICO;
+        assert_eq!(game_winner(&[90, 100], Some(0), &rules), None);
+        assert_eq!(game_winner(&[90, 101], Some(0), &rules), Some(1));
+        assert_eq!(game_winner(&[120, 105], Some(1), &rules), Some(1));
+        assert_eq!(game_winner(&[120, 105], None, &rules), Some(0));
+    }
+}
@@ -0,0 +1,101 @@
+/// Where a seat sits around the table, seen by the local player who always sits at the bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeatPosition {
+    Bottom,
+    Right,
+    Top,
+    Left,
+}
+
+/// The players around the table. Seats are numbered counter-clockwise starting from the
+/// local player, which is also the turn order. With four players the seats in front of each
+/// other play as a team, with two or three everyone plays on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seats {
+    players: usize,
+}
+
+impl Seats {
+    pub fn new(players: usize) -> Self {
+        assert!(
+            (2..=4).contains(&players),
+            "guiñote is played by 2, 3 or 4 players"
+        );
+        Seats { players }
+    }
+
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    pub fn is_partnership(&self) -> bool {
+        self.players == 4
+    }
+
+    /// The number of sides that score, teams in a partnership game or else each player
+    pub fn sides(&self) -> usize {
+        if self.is_partnership() {
+            2
+        } else {
+            self.players
+        }
+    }
+
+    pub fn side(&self, seat: usize) -> usize {
+        if self.is_partnership() {
+            seat % 2
+        } else {
+            seat
+        }
+    }
+
+    /// The seat playing after `seat`
+    pub fn next(&self, seat: usize) -> usize {
+        (seat + 1) % self.players
+    }
+
+    /// The seat playing before `seat`
+    pub fn previous(&self, seat: usize) -> usize {
+        (seat + self.players - 1) % self.players
+    }
+
+    /// Every seat in turn order starting from `seat`
+    pub fn from(&self, seat: usize) -> impl Iterator<Item = usize> {
+        let players = self.players;
+        (0..players).map(move |i| (seat + i) % players)
+    }
+
+    pub fn position(&self, seat: usize) -> SeatPosition {
+        match (self.players, seat) {
+            (_, 0) => SeatPosition::Bottom,
+            (2, _) => SeatPosition::Top,
+            (_, 1) => SeatPosition::Right,
+            (3, _) => SeatPosition::Left,
+            (_, 2) => SeatPosition::Top,
+            _ => SeatPosition::Left,
+        }
+    }
+
+    pub fn name(&self, seat: usize) -> &'static str {
+        match (self.position(seat), self.players) {
+            (SeatPosition::Bottom, _) => "You",
+            (SeatPosition::Top, 2) => "Opponent",
+            (SeatPosition::Top, _) => "Partner",
+            (SeatPosition::Right, _) => "Right opponent",
+            (SeatPosition::Left, _) => "Left opponent",
+        }
+    }
+
+    pub fn short_name(&self, seat: usize) -> &'static str {
+        match self.position(seat) {
+            SeatPosition::Bottom => "You",
+            SeatPosition::Right => "Right",
+            SeatPosition::Top => "Top",
+            SeatPosition::Left => "Left",
+        }
+    }
+
+    pub fn seat_at(&self, position: SeatPosition) -> Option<usize> {
+        (0..self.players).find(|&seat| self.position(seat) == position)
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+
+use crate::{
+    rules::{self, Phase},
+    ruleset::RuleSet,
+    scoring,
+    seats::Seats,
+    trick::Trick,
+    Card, CardsValues, GameState, Palos,
+};
+
+/// The best play of the arrastre from a position and how the hand ends with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    //the index in the hand of the seat whose turn it is
+    pub card: usize,
+    //the points of each side at the end of the hand
+    pub points: Vec<u16>,
+}
+
+/// A play that loses points against the best one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mistake {
+    //by how many points the side of the seat ends the hand worse against the other side
+    pub cost: u16,
+    pub best: Solution,
+}
+
+/// The best play for the seat whose turn it is and the points of the hand when everyone plays
+/// perfectly. Once the talon runs out every card is known to a player who can count them, but
+/// only between two players, so it is only solved in the arrastre of a two player game
+pub fn solve(game: &GameState) -> Option<Solution> {
+    let mut solver = Solver::new(game)?;
+    let position = Position::of(game);
+    let (card, gains) = solver.best(&position);
+    let card = game.hand(game.turn()).iter().position(|held| *held == card);
+    Some(Solution {
+        card: card.expect("the best card is in the hand"),
+        points: solver.final_points(game, gains),
+    })
+}
+
+/// The points of each side at the end of the hand if the seat whose turn it is plays the card
+/// at `card` and everyone plays perfectly after it
+pub fn solve_play(game: &GameState, card: usize) -> Option<Vec<u16>> {
+    if !game.playable_cards(game.turn()).contains(&card) {
+        return None;
+    }
+    let mut solver = Solver::new(game)?;
+    let position = Position::of(game);
+    let gains = solver.play(&position, game.hand(game.turn())[card], i32::MIN, i32::MAX);
+    Some(solver.final_points(game, gains))
+}
+
+/// The mistake of playing the card at `card`, if it is one
+pub fn mistake(game: &GameState, card: usize) -> Option<Mistake> {
+    let played = solve_play(game, card)?;
+    let best = solve(game)?;
+    let side = game.seats().side(game.turn());
+    let margin = |points: &[u16]| i32::from(points[side]) - i32::from(points[1 - side]);
+    let cost = margin(&best.points) - margin(&played);
+    (cost > 0).then_some(Mistake {
+        cost: cost as u16,
+        best,
+    })
+}
+
+/// The cards of each seat and what else decides how the rest of the hand can go, between
+/// two tricks or in the middle of one
+#[derive(Debug, Clone)]
+struct Position {
+    hands: [u64; 4],
+    trick: Trick,
+    //the palos sung and the sides that won a trick, as bits
+    sung: u8,
+    won: u8,
+}
+
+impl Position {
+    fn of(game: &GameState) -> Self {
+        let mut hands = [0; 4];
+        for (seat, hand) in hands.iter_mut().enumerate().take(game.seats().players()) {
+            *hand = game
+                .hand(seat)
+                .iter()
+                .fold(0, |hand, card| hand | bit(card));
+        }
+        let sung = game
+            .sung()
+            .iter()
+            .fold(0, |sung, palo| sung | palo_bit(*palo));
+        let won = (0..game.seats().sides())
+            .filter(|&side| !game.hand_score().bazas[side].is_empty())
+            .fold(0, |won, side| won | 1 << side);
+        Position {
+            hands,
+            trick: game.trick().clone(),
+            sung,
+            won,
+        }
+    }
+}
+
+/// A position between two tricks, which the transposition table keeps the outcome of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    hands: [u64; 4],
+    leader: usize,
+    sung: u8,
+    won: u8,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact,
+    //the outcome is at least this good for the side that is solved, or at most
+    Lower,
+    Upper,
+}
+
+/// Alpha-beta search over the points each side still makes in the hand, as the margin of the
+/// side whose turn it is at the root
+struct Solver {
+    seats: Seats,
+    rules: RuleSet,
+    triunfo: Palos,
+    side: usize,
+    table: HashMap<Key, ([i32; 2], Bound)>,
+}
+
+impl Solver {
+    fn new(game: &GameState) -> Option<Self> {
+        let seats = game.seats();
+        let seat = game.turn();
+        if seats.players() != 2
+            || game.play_phase() != Phase::Arrastre
+            || game.phase().is_over()
+            || game.hand(seat).is_empty()
+        {
+            return None;
+        }
+        Some(Solver {
+            seats,
+            rules: *game.rules(),
+            triunfo: game.triunfo(),
+            side: seats.side(seat),
+            table: HashMap::new(),
+        })
+    }
+
+    fn margin(&self, gains: [i32; 2]) -> i32 {
+        gains[self.side] - gains[1 - self.side]
+    }
+
+    fn final_points(&self, game: &GameState, gains: [i32; 2]) -> Vec<u16> {
+        (0..2)
+            .map(|side| game.hand_score().total(side) + gains[side] as u16)
+            .collect()
+    }
+
+    /// The best card and what each side makes with it
+    fn best(&mut self, position: &Position) -> (Card, [i32; 2]) {
+        let mut best: Option<(Card, [i32; 2])> = None;
+        let mut alpha = i32::MIN;
+        for card in self.legal_cards(position) {
+            //a card only needs to be searched when it may beat the best one so far
+            let gains = self.play(position, card, alpha, i32::MAX);
+            if best.is_none_or(|(_, best)| self.margin(gains) > self.margin(best)) {
+                best = Some((card, gains));
+                alpha = self.margin(gains);
+            }
+        }
+        best.expect("the seat whose turn it is has cards")
+    }
+
+    fn turn(&self, position: &Position) -> usize {
+        (position.trick.leader() + position.trick.len()) % self.seats.players()
+    }
+
+    fn legal_cards(&self, position: &Position) -> Vec<Card> {
+        let seat = self.turn(position);
+        let hand = cards(position.hands[seat]);
+        let partner_winning = position.trick.winner(self.triunfo).is_some_and(|winner| {
+            winner != seat && self.seats.side(winner) == self.seats.side(seat)
+        });
+        let mut legal = rules::legal_moves(
+            &hand,
+            &position.trick,
+            self.triunfo,
+            Phase::Arrastre,
+            partner_winning,
+            &self.rules,
+        )
+        .into_iter()
+        .map(|index| hand[index])
+        .collect::<Vec<Card>>();
+        //the cards worth most first, they decide the hand and make the cutoffs early
+        legal.sort_by_key(|card| std::cmp::Reverse((card.value(), card.kill_power())));
+        legal
+    }
+
+    fn play(&mut self, position: &Position, card: Card, alpha: i32, beta: i32) -> [i32; 2] {
+        let seat = self.turn(position);
+        let mut next = position.clone();
+        next.hands[seat] &= !bit(&card);
+        next.trick.play(seat, card);
+        if next.trick.len() < self.seats.players() {
+            return self.search(&next, alpha, beta);
+        }
+
+        //the trick is complete, its winner leads the next one after singing
+        let winner = next
+            .trick
+            .winner(self.triunfo)
+            .expect("a finished trick has cards");
+        let side = self.seats.side(winner);
+        let mut gains = [0; 2];
+        gains[side] += i32::from(next.trick.points());
+        next.won |= 1 << side;
+        if next.hands.iter().all(|hand| *hand == 0) {
+            gains[side] += i32::from(scoring::ULTIMAS);
+            if self.rules.capote_bonus > 0 && next.won == 1 << side {
+                gains[side] += i32::from(self.rules.capote_bonus);
+            }
+            return gains;
+        }
+        gains[side] += self.sing(&mut next, winner);
+        next.trick = Trick::new(winner);
+        let margin = self.margin(gains);
+        let rest = self.search(
+            &next,
+            alpha.saturating_sub(margin),
+            beta.saturating_sub(margin),
+        );
+        [gains[0] + rest[0], gains[1] + rest[1]]
+    }
+
+    fn sing(&self, position: &mut Position, winner: usize) -> i32 {
+        //after winning a trick its side sings the best cante, or else the winner sings them
+        //all before leading, as the game allows
+        let singers = if self.rules.cante_requires_trick {
+            self.seats
+                .from(winner)
+                .filter(|&seat| self.seats.side(seat) == self.seats.side(winner))
+                .collect::<Vec<usize>>()
+        } else {
+            vec![winner]
+        };
+        let mut cantes = Palos::ALL
+            .into_iter()
+            .filter(|palo| position.sung & palo_bit(*palo) == 0)
+            .filter(|palo| {
+                let pair = [CardsValues::Rey, CardsValues::Caballo]
+                    .map(|value| bit(&Card { value, palo: *palo }))
+                    .iter()
+                    .fold(0, |pair, bit| pair | bit);
+                singers
+                    .iter()
+                    .any(|&seat| position.hands[seat] & pair == pair)
+            })
+            .collect::<Vec<Palos>>();
+        cantes.sort_by_key(|palo| *palo != self.triunfo);
+        if self.rules.cante_requires_trick {
+            cantes.truncate(1);
+        }
+        cantes
+            .into_iter()
+            .map(|palo| {
+                position.sung |= palo_bit(palo);
+                if palo == self.triunfo {
+                    40
+                } else {
+                    20
+                }
+            })
+            .sum()
+    }
+
+    fn search(&mut self, position: &Position, mut alpha: i32, mut beta: i32) -> [i32; 2] {
+        let key = position.trick.is_empty().then(|| Key {
+            hands: position.hands,
+            leader: position.trick.leader(),
+            sung: position.sung,
+            won: position.won,
+        });
+        if let Some((gains, bound)) = key.and_then(|key| self.table.get(&key)).copied() {
+            let margin = self.margin(gains);
+            match bound {
+                Bound::Exact => return gains,
+                Bound::Lower => alpha = alpha.max(margin),
+                Bound::Upper => beta = beta.min(margin),
+            }
+            if alpha >= beta {
+                return gains;
+            }
+        }
+
+        let (start_alpha, start_beta) = (alpha, beta);
+        let maximizing = self.seats.side(self.turn(position)) == self.side;
+        let mut best: Option<[i32; 2]> = None;
+        for card in self.legal_cards(position) {
+            let gains = self.play(position, card, alpha, beta);
+            let margin = self.margin(gains);
+            if maximizing {
+                if best.is_none_or(|best| margin > self.margin(best)) {
+                    best = Some(gains);
+                }
+                alpha = alpha.max(margin);
+            } else {
+                if best.is_none_or(|best| margin < self.margin(best)) {
+                    best = Some(gains);
+                }
+                beta = beta.min(margin);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        let best = best.expect("the seat whose turn it is has cards");
+        if let Some(key) = key {
+            let margin = self.margin(best);
+            let bound = if margin <= start_alpha {
+                Bound::Upper
+            } else if margin >= start_beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            self.table.insert(key, (best, bound));
+        }
+        best
+    }
+}
+
+fn bit(card: &Card) -> u64 {
+    let palo = Palos::ALL.iter().position(|palo| *palo == card.palo);
+    let value = CardsValues::ALL
+        .iter()
+        .position(|value| *value == card.value);
+    1 << (palo.expect("every palo is in ALL") * CardsValues::ALL.len()
+        + value.expect("every value is in ALL"))
+}
+
+fn palo_bit(palo: Palos) -> u8 {
+    1 << Palos::ALL
+        .iter()
+        .position(|other| *other == palo)
+        .expect("every palo is in ALL")
+}
+
+/// The cards of a hand kept as bits
+fn cards(hand: u64) -> Vec<Card> {
+    Palos::ALL
+        .into_iter()
+        .flat_map(|palo| {
+            CardsValues::ALL
+                .into_iter()
+                .map(move |value| Card { value, palo })
+        })
+        .filter(|card| hand & bit(card) != 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai, Action, GameEvent};
+
+    //the sides sing as the solver expects, the best cante after a won trick
+    fn sing(game: &mut GameState) {
+        while let Some(seat) = (0..2).find(|&seat| !game.available_cantes(seat).is_empty()) {
+            game.apply(Action::Sing { seat }).unwrap();
+        }
+    }
+
+    /// The margin of `side` at the end of the hand, trying every card of every seat
+    fn brute_force(game: &GameState, side: usize) -> i32 {
+        let seat = game.turn();
+        let margins = game
+            .playable_cards(seat)
+            .into_iter()
+            .map(|card| brute_force_play(game, card, side));
+        if game.seats().side(seat) == side {
+            margins.max()
+        } else {
+            margins.min()
+        }
+        .expect("the seat whose turn it is has cards")
+    }
+
+    fn brute_force_play(game: &GameState, card: usize, side: usize) -> i32 {
+        let mut next = game.clone();
+        let seat = game.turn();
+        for event in next.apply(Action::Play { seat, card }).unwrap() {
+            if let GameEvent::HandScored { score, .. } = event {
+                return i32::from(score.total(side)) - i32::from(score.total(1 - side));
+            }
+        }
+        sing(&mut next);
+        brute_force(&next, side)
+    }
+
+    /// The positions of the arrastre of the first hand with few cards left, as the heuristic
+    /// plays them
+    fn endgames(rules: RuleSet, seed: u64) -> Vec<GameState> {
+        let mut game = GameState::new(Seats::new(2), rules, 0, false, seed);
+        let mut endgames = Vec::new();
+        ai::play_out_heuristic(&mut game, |game, action| {
+            if game
+                .log()
+                .iter()
+                .any(|event| matches!(event, GameEvent::HandScored { .. }))
+            {
+                return false;
+            }
+            if game.play_phase() == Phase::Arrastre
+                && game.hand(game.turn()).len() <= 4
+                && matches!(action, Action::Play { .. })
+            {
+                endgames.push(game.clone());
+            }
+            true
+        });
+        endgames
+    }
+
+    #[test]
+    fn solves_as_brute_force() {
+        let mut solved = 0;
+        for rules in RuleSet::PRESETS {
+            for seed in 0..6 {
+                for game in endgames(rules, seed) {
+                    let side = game.seats().side(game.turn());
+                    let margin =
+                        |points: &[u16]| i32::from(points[side]) - i32::from(points[1 - side]);
+                    let solution = solve(&game).unwrap();
+                    assert_eq!(margin(&solution.points), brute_force(&game, side));
+                    assert_eq!(
+                        margin(&solution.points),
+                        brute_force_play(&game, solution.card, side)
+                    );
+                    for card in game.playable_cards(game.turn()) {
+                        let points = solve_play(&game, card).unwrap();
+                        assert_eq!(margin(&points), brute_force_play(&game, card, side));
+                    }
+                    solved += 1;
+                }
+            }
+        }
+        assert!(solved > 100);
+    }
+
+    #[test]
+    fn only_solves_the_arrastre_between_two() {
+        let draw = GameState::new(Seats::new(2), RuleSet::CLASICO, 0, false, 1);
+        assert_eq!(solve(&draw), None);
+        let mut four = GameState::new(Seats::new(4), RuleSet::CLASICO, 0, false, 1);
+        ai::play_out_heuristic(&mut four, |game, _| game.play_phase() != Phase::Arrastre);
+        assert_eq!(solve(&four), None);
+    }
+}
@@ -0,0 +1,1103 @@
+use std::fmt;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cante::{self, Cante},
+    deck::{self, Talon},
+    event::{Events, GameEvent},
+    phase::{GamePhase, InvalidTransition},
+    player::Observation,
+    rules::{self, Phase},
+    ruleset::RuleSet,
+    scoring::{self, HandScore},
+    seats::Seats,
+    trick::Trick,
+    Card, CardsValues, Palos,
+};
+
+/// Something a seat does, on its turn or whenever the rules allow it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    //the card at this index of the seat's hand
+    Play { seat: usize, card: usize },
+    Sing { seat: usize },
+    SingTute { seat: usize },
+    Exchange { seat: usize },
+}
+
+impl Action {
+    /// The seat taking the action
+    pub fn seat(&self) -> usize {
+        match *self {
+            Action::Play { seat, .. }
+            | Action::Sing { seat }
+            | Action::SingTute { seat }
+            | Action::Exchange { seat } => seat,
+        }
+    }
+}
+
+/// Why an action can not be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    GameOver,
+    NotYourTurn,
+    NoSuchSeat,
+    NoSuchCard,
+    IllegalMove(String),
+    CannotSing { requires_trick: bool },
+    NothingToSing,
+    NoTute,
+    NothingToExchange,
+    Phase(InvalidTransition),
+    //a replayed log that does not start with the game, or starts it twice, or has an event
+    //that could not have happened
+    NotStarted,
+    AlreadyStarted,
+    InvalidEvent(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::GameOver => f.write_str("The game is over"),
+            RuleError::NotYourTurn => f.write_str("Wait for your turn to play"),
+            RuleError::NoSuchSeat => f.write_str("There is no such seat"),
+            RuleError::NoSuchCard => f.write_str("There is no such card"),
+            RuleError::IllegalMove(reason) => f.write_str(reason),
+            RuleError::CannotSing {
+                requires_trick: true,
+            } => f.write_str("You can only sing after winning a trick"),
+            RuleError::CannotSing {
+                requires_trick: false,
+            } => f.write_str("You can only sing before leading"),
+            RuleError::NothingToSing => f.write_str("There is nothing to sing"),
+            RuleError::NoTute => f.write_str("There is no tute to sing"),
+            RuleError::NothingToExchange => f.write_str("There is nothing to exchange"),
+            RuleError::Phase(invalid) => invalid.fmt(f),
+            RuleError::NotStarted => f.write_str("The game has not started"),
+            RuleError::AlreadyStarted => f.write_str("The game has already started"),
+            RuleError::InvalidEvent(reason) => write!(f, "The log is not valid: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+impl From<InvalidTransition> for RuleError {
+    fn from(invalid: InvalidTransition) -> Self {
+        RuleError::Phase(invalid)
+    }
+}
+
+/// A single game, from the first deal until a side wins it. Every change to it is a
+/// `GameEvent` folded into it and kept in its log
+#[derive(Debug, Clone)]
+pub struct GameState {
+    seats: Seats,
+    rules: RuleSet,
+    //deals the current hand, the next seat is the mano and leads the first trick
+    dealer: usize,
+    //the seat before the dealer cuts the deck before dealing
+    cut_deck: bool,
+    //the shuffle of every hand is decided by the seed and the number of hands dealt before it
+    seed: u64,
+    hands_dealt: u64,
+    //points of the previous hands for each side, the current one is kept in `hand_score`
+    points: Vec<u16>,
+    hand_score: HandScore,
+    //nobody reached the winning points in the first hand, the points carry over to this one
+    vueltas: bool,
+    //the hand of each seat, seat 0 is the local player
+    hands: Vec<Vec<Card>>,
+    triunfo: Palos,
+    talon: Talon,
+    trick: Trick,
+    last_trick: Option<Trick>,
+    phase: GamePhase,
+    sung: Vec<Palos>,
+    //cards shown to the other side when singing
+    revealed: Vec<Card>,
+    //the seat that sang tute and with which figure, it ends the game
+    tute: Option<(usize, CardsValues)>,
+    log: Events,
+}
+
+impl GameState {
+    /// A new game with its first hand dealt by `dealer`. The same `seed` always deals the
+    /// same cards
+    pub fn new(seats: Seats, rules: RuleSet, dealer: usize, cut_deck: bool, seed: u64) -> Self {
+        let mut game = Self::started(seats, rules, cut_deck, seed);
+        let (hands, talon) = deck::deal(
+            seats.players(),
+            seats.next(dealer),
+            cut_deck,
+            &mut game.deal_rng(),
+        );
+        game.emit(
+            GameEvent::Dealt {
+                dealer,
+                hands,
+                talon,
+            },
+            &mut Vec::new(),
+        )
+        .expect("a new game can be dealt");
+        game
+    }
+
+    /// The state of a game rebuilt from its log, which starts with `GameEvent::GameStarted`.
+    /// An event that could not have happened fails with `RuleError::InvalidEvent`
+    pub fn replay(events: &[GameEvent]) -> Result<Self, RuleError> {
+        let Some((
+            GameEvent::GameStarted {
+                seats,
+                rules,
+                cut_deck,
+                seed,
+            },
+            events,
+        )) = events.split_first()
+        else {
+            return Err(RuleError::NotStarted);
+        };
+        let mut game = Self::started(*seats, *rules, *cut_deck, *seed);
+        for event in events {
+            game.emit(event.clone(), &mut Vec::new())
+                .map_err(|error| match error {
+                    RuleError::Phase(transition) => invalid(transition.to_string()),
+                    error => error,
+                })?;
+        }
+        Ok(game)
+    }
+
+    fn started(seats: Seats, rules: RuleSet, cut_deck: bool, seed: u64) -> Self {
+        //nothing is dealt yet, the first `GameEvent::Dealt` sets the hands and the triunfo
+        GameState {
+            seats,
+            rules,
+            dealer: 0,
+            cut_deck,
+            seed,
+            hands_dealt: 0,
+            points: vec![0; seats.sides()],
+            hand_score: HandScore::new(seats.sides()),
+            vueltas: false,
+            hands: vec![Vec::new(); seats.players()],
+            triunfo: Palos::Oros,
+            talon: Talon::default(),
+            trick: Trick::new(0),
+            last_trick: None,
+            phase: GamePhase::Dealing,
+            sung: Vec::new(),
+            revealed: Vec::new(),
+            tute: None,
+            log: vec![GameEvent::GameStarted {
+                seats,
+                rules,
+                cut_deck,
+                seed,
+            }],
+        }
+    }
+
+    /// The game as the seat of `observation` may imagine it, with `hands` for every seat and
+    /// `talon` for the face down cards it can not see. Neither is the seed seen, so the hands
+    /// dealt after this one are not the ones of the game
+    pub fn imagined(observation: &Observation, hands: Vec<Vec<Card>>, talon: Vec<Card>) -> Self {
+        let mut face_down = Talon::default();
+        face_down.replace_cards(talon);
+        if let Some(card) = observation.triunfo_card() {
+            face_down.exchange_triunfo_card(*card);
+        }
+        let log = observation.log().to_vec();
+        GameState {
+            seats: observation.seats(),
+            rules: *observation.rules(),
+            dealer: observation.dealer(),
+            cut_deck: observation.cut_deck(),
+            seed: 0,
+            hands_dealt: log
+                .iter()
+                .filter(|event| matches!(event, GameEvent::Dealt { .. }))
+                .count() as u64,
+            points: observation.points().to_vec(),
+            hand_score: observation.hand_score().clone(),
+            vueltas: observation.is_vueltas(),
+            hands,
+            triunfo: observation.triunfo(),
+            talon: face_down,
+            trick: observation.trick().clone(),
+            last_trick: observation.last_trick().cloned(),
+            phase: observation.phase(),
+            sung: observation.sung().to_vec(),
+            revealed: observation.revealed().to_vec(),
+            tute: observation.tute(),
+            log,
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<Events, RuleError> {
+        if self.phase.is_over() {
+            return Err(RuleError::GameOver);
+        }
+        if action.seat() >= self.seats.players() {
+            return Err(RuleError::NoSuchSeat);
+        }
+        let mut events = Vec::new();
+        match action {
+            Action::Play { seat, card } => self.play_card(seat, card, &mut events)?,
+            Action::Sing { seat } => self.sing(seat, &mut events)?,
+            Action::SingTute { seat } => self.sing_tute(seat, &mut events)?,
+            Action::Exchange { seat } => self.exchange_siete(seat, &mut events)?,
+        }
+        Ok(events)
+    }
+
+    /// Every event of the game since it started
+    pub fn log(&self) -> &[GameEvent] {
+        &self.log
+    }
+
+    pub fn seats(&self) -> Seats {
+        self.seats
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    pub fn mano(&self) -> usize {
+        self.seats.next(self.dealer)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn cut_deck(&self) -> bool {
+        self.cut_deck
+    }
+
+    pub fn cutter(&self) -> Option<usize> {
+        self.cut_deck.then(|| self.seats.previous(self.dealer))
+    }
+
+    /// The points of each side in the previous hands
+    pub fn points(&self) -> &[u16] {
+        &self.points
+    }
+
+    /// The points of `side` including the hand being played
+    pub fn score(&self, side: usize) -> u16 {
+        self.points[side] + self.hand_score.total(side)
+    }
+
+    pub fn hand(&self, seat: usize) -> &[Card] {
+        &self.hands[seat]
+    }
+
+    /// The tricks and cantes of each side in the hand being played
+    pub fn hand_score(&self) -> &HandScore {
+        &self.hand_score
+    }
+
+    /// The palos whose cante has been sung in the hand being played
+    pub fn sung(&self) -> &[Palos] {
+        &self.sung
+    }
+
+    pub fn triunfo(&self) -> Palos {
+        self.triunfo
+    }
+
+    pub fn talon(&self) -> &Talon {
+        &self.talon
+    }
+
+    pub fn revealed(&self) -> &[Card] {
+        &self.revealed
+    }
+
+    pub fn tute(&self) -> Option<(usize, CardsValues)> {
+        self.tute
+    }
+
+    /// The side that won the game, once it is over
+    pub fn winner(&self) -> Option<usize> {
+        match self.phase {
+            GamePhase::GameOver { side } => Some(side),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    /// Whether the hand being played is one of the vueltas
+    pub fn is_vueltas(&self) -> bool {
+        self.vueltas
+    }
+
+    /// Whether the hand is in the arrastre, which decides the cards that can be played
+    pub fn play_phase(&self) -> Phase {
+        if self.talon.is_empty() {
+            Phase::Arrastre
+        } else {
+            Phase::Draw
+        }
+    }
+
+    pub fn turn(&self) -> usize {
+        (self.trick.leader() + self.trick.len()) % self.seats.players()
+    }
+
+    pub fn playable_cards(&self, seat: usize) -> Vec<usize> {
+        if seat != self.turn() {
+            return (0..self.hands[seat].len()).collect();
+        }
+        rules::legal_moves(
+            &self.hands[seat],
+            &self.trick,
+            self.triunfo,
+            self.play_phase(),
+            self.is_partner_winning(seat),
+            &self.rules,
+        )
+    }
+
+    /// The trick being played, empty until someone leads
+    pub fn trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    /// The last trick won in the hand being played
+    pub fn last_trick(&self) -> Option<&Trick> {
+        self.last_trick.as_ref()
+    }
+
+    pub fn table_card(&self, seat: usize) -> Option<&Card> {
+        //the cards of the trick being played, or the last trick until someone leads
+        if self.trick.is_empty() {
+            self.last_trick.as_ref()?.card_of(seat)
+        } else {
+            self.trick.card_of(seat)
+        }
+    }
+
+    pub fn available_cantes(&self, seat: usize) -> Vec<Cante> {
+        if !self.may_sing(seat) {
+            return Vec::new();
+        }
+        cante::available_cantes(&self.hands[seat], &self.sung, self.triunfo)
+    }
+
+    pub fn tute_figure(&self, seat: usize) -> Option<CardsValues> {
+        if !self.rules.tute || !self.may_sing(seat) {
+            return None;
+        }
+        cante::tute(&self.hands[seat])
+    }
+
+    pub fn exchangeable_card(&self, seat: usize) -> Option<usize> {
+        if !self.won_last_trick(seat) {
+            return None;
+        }
+        let triunfo_card = self.talon.triunfo_card()?;
+        rules::exchange_card(&self.hands[seat], triunfo_card, self.rules.dos_exchange)
+    }
+
+    fn set_phase(&mut self, to: GamePhase) -> Result<(), RuleError> {
+        Ok(self.phase.transition(to)?)
+    }
+
+    fn deal_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed.wrapping_add(self.hands_dealt))
+    }
+
+    fn emit(&mut self, event: GameEvent, events: &mut Events) -> Result<(), RuleError> {
+        self.fold(&event)?;
+        self.log.push(event.clone());
+        events.push(event);
+        Ok(())
+    }
+
+    fn fold(&mut self, event: &GameEvent) -> Result<(), RuleError> {
+        //a replayed log may come from anywhere, the events are checked before they are folded,
+        //the actions by the same checks `apply` makes
+        if self.phase.is_over() {
+            return Err(invalid("the game is already over".to_string()));
+        }
+        match event {
+            GameEvent::GameStarted { .. } => return Err(RuleError::AlreadyStarted),
+            GameEvent::Dealt {
+                dealer,
+                hands,
+                talon,
+            } => {
+                self.check_seat(*dealer)?;
+                //every deal is decided by the seed, see `deal_rng`
+                let (dealt, stock) = deck::deal(
+                    self.seats.players(),
+                    self.seats.next(*dealer),
+                    self.cut_deck,
+                    &mut self.deal_rng(),
+                );
+                if *hands != dealt || *talon != stock {
+                    return Err(invalid(
+                        "the cards are not the ones the seed deals".to_string(),
+                    ));
+                }
+                let Some(triunfo_card) = talon.triunfo_card() else {
+                    return Err(invalid("the deal has no triunfo card".to_string()));
+                };
+                if self.phase != GamePhase::Dealing {
+                    self.set_phase(GamePhase::Dealing)?;
+                }
+                self.dealer = *dealer;
+                self.hands_dealt += 1;
+                self.hands = hands.clone();
+                self.triunfo = triunfo_card.palo;
+                self.talon = talon.clone();
+                self.hand_score = HandScore::new(self.seats.sides());
+                self.trick = Trick::new(self.mano());
+                self.last_trick = None;
+                self.sung.clear();
+                self.revealed.clear();
+                self.set_phase(GamePhase::playing(self.play_phase()))?;
+            }
+            GameEvent::CardPlayed { seat, card } => {
+                let index = self.held_card(*seat, card)?;
+                self.check_play(*seat, index)
+                    .map_err(|error| not_allowed(*seat, "play", card, error))?;
+                self.close_cante_window()?;
+                self.hands[*seat].remove(index);
+                self.trick.play(*seat, *card);
+            }
+            GameEvent::TrickWon { seat, points } => {
+                if self.trick.len() < self.seats.players() {
+                    return Err(invalid("the trick is not over".to_string()));
+                }
+                if self.trick.winner(self.triunfo) != Some(*seat) {
+                    return Err(invalid(format!("seat {} did not win the trick", seat)));
+                }
+                if *points != self.trick.points() {
+                    return Err(invalid(format!("the trick is not worth {} points", points)));
+                }
+                let trick = std::mem::replace(&mut self.trick, Trick::new(*seat));
+                self.hand_score
+                    .add_trick(trick.clone(), self.seats.side(*seat));
+                self.last_trick = Some(trick);
+                self.draw_cards(*seat);
+                if self.hands.iter().all(|hand| hand.is_empty()) {
+                    self.set_phase(GamePhase::Scoring)?;
+                } else {
+                    self.set_phase(GamePhase::CanteWindow { seat: *seat })?;
+                }
+            }
+            GameEvent::CanteSung {
+                seat,
+                cante,
+                points,
+            } => {
+                self.check_seat(*seat)?;
+                if *points != cante.points(self.triunfo) {
+                    return Err(invalid(format!("a cante is not worth {} points", points)));
+                }
+                let first = self.cante_to_sing(*seat).map_err(|error| {
+                    invalid(format!(
+                        "seat {} could not sing in {}: {}",
+                        seat, cante.palo, error
+                    ))
+                })?;
+                if first != *cante {
+                    return Err(invalid(format!(
+                        "seat {} sings in {} before {}",
+                        seat, first.palo, cante.palo
+                    )));
+                }
+                self.hand_score.add_cante(*points, self.seats.side(*seat));
+                self.sung.push(cante.palo);
+                self.revealed.extend(cante.cards());
+                self.close_cante_window()?;
+            }
+            GameEvent::TuteSung { seat, figure } => {
+                self.check_seat(*seat)?;
+                if self.tute_to_sing(*seat) != Ok(*figure) {
+                    return Err(invalid(format!(
+                        "seat {} could not sing tute of the {}",
+                        seat,
+                        figure.name()
+                    )));
+                }
+                self.revealed.extend(
+                    self.hands[*seat]
+                        .iter()
+                        .filter(|card| card.value == *figure)
+                        .copied()
+                        .collect::<Vec<Card>>(),
+                );
+                self.tute = Some((*seat, *figure));
+            }
+            GameEvent::Exchanged {
+                seat,
+                card,
+                triunfo_card,
+            } => {
+                let index = self.held_card(*seat, card)?;
+                let exchangeable = self
+                    .card_to_exchange(*seat)
+                    .map_err(|error| not_allowed(*seat, "exchange", card, error))?;
+                if exchangeable != index {
+                    return Err(not_allowed(
+                        *seat,
+                        "exchange",
+                        card,
+                        RuleError::NothingToExchange,
+                    ));
+                }
+                if self.talon.triunfo_card() != Some(triunfo_card) {
+                    return Err(invalid(format!(
+                        "the {} of {} is not the triunfo card",
+                        triunfo_card.name(),
+                        triunfo_card.palo
+                    )));
+                }
+                self.talon.exchange_triunfo_card(*card);
+                self.hands[*seat][index] = *triunfo_card;
+            }
+            GameEvent::HandScored { score, .. } => {
+                //a hand is scored when its last trick is won, or in the vueltas as soon as a side
+                //reaches the winning points
+                let mut played = self.hand_score.clone();
+                let last_winner = self
+                    .last_trick
+                    .as_ref()
+                    .and_then(|trick| trick.winner(self.triunfo));
+                let card_points = (0..self.seats.sides())
+                    .map(|side| played.card_points(side))
+                    .sum::<u16>();
+                match (self.phase, last_winner) {
+                    (GamePhase::Scoring, Some(winner))
+                        if card_points + scoring::ULTIMAS == scoring::HAND_TOTAL =>
+                    {
+                        played.finish(self.seats.side(winner), &self.rules);
+                    }
+                    _ if self.vueltas_winner().is_some() => {}
+                    _ => return Err(invalid("the hand is scored before it is over".to_string())),
+                }
+                if *score != played {
+                    return Err(invalid("the scored hand is not the one played".to_string()));
+                }
+                for (side, points) in self.points.iter_mut().enumerate() {
+                    *points = points.saturating_add(score.total(side));
+                }
+                self.hand_score = HandScore::new(self.seats.sides());
+            }
+            GameEvent::Vueltas { hand_points } => {
+                let Some(GameEvent::HandScored { score, .. }) = self.log.last() else {
+                    return Err(invalid(
+                        "the vueltas come before the hand is scored".to_string(),
+                    ));
+                };
+                let scored = (0..self.seats.sides())
+                    .map(|side| score.total(side))
+                    .collect::<Vec<u16>>();
+                if *hand_points != scored {
+                    return Err(invalid(
+                        "the hand points are not the ones scored".to_string(),
+                    ));
+                }
+                if scoring::game_winner(&self.points, score.ultimas, &self.rules).is_some() {
+                    return Err(invalid("there are no vueltas, the game is won".to_string()));
+                }
+                self.set_phase(GamePhase::Vueltas)?;
+                self.vueltas = true;
+            }
+            GameEvent::GameWon { side } => {
+                //the game is won singing tute, or by the points when a hand is scored
+                let winner = match self.log.last() {
+                    Some(GameEvent::TuteSung { seat, .. }) => Some(self.seats.side(*seat)),
+                    Some(GameEvent::HandScored { score, .. })
+                        if self.phase == GamePhase::Scoring =>
+                    {
+                        scoring::game_winner(&self.points, score.ultimas, &self.rules)
+                    }
+                    Some(GameEvent::HandScored { .. }) => self.vueltas_winner(),
+                    _ => None,
+                };
+                if winner != Some(*side) {
+                    return Err(invalid(format!("side {} did not win the game", side)));
+                }
+                self.set_phase(GamePhase::GameOver { side: *side })?
+            }
+        }
+        Ok(())
+    }
+
+    fn check_seat(&self, seat: usize) -> Result<(), RuleError> {
+        if seat >= self.seats.players() {
+            return Err(invalid(format!("there is no seat {}", seat)));
+        }
+        Ok(())
+    }
+
+    /// The index of `card` in the hand of `seat`, for an event that takes it from there
+    fn held_card(&self, seat: usize, card: &Card) -> Result<usize, RuleError> {
+        self.check_seat(seat)?;
+        self.hands[seat]
+            .iter()
+            .position(|held| held == card)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "seat {} does not hold the {} of {}",
+                    seat,
+                    card.name(),
+                    card.palo
+                ))
+            })
+    }
+
+    fn play_card(
+        &mut self,
+        seat: usize,
+        card: usize,
+        events: &mut Events,
+    ) -> Result<(), RuleError> {
+        self.check_play(seat, card)?;
+        let card = self.hands[seat][card];
+        self.emit(GameEvent::CardPlayed { seat, card }, events)?;
+        if self.trick.len() == self.seats.players() {
+            self.finish_trick(events)?;
+        }
+        Ok(())
+    }
+
+    /// Whether `seat` may play the card at `card` now
+    fn check_play(&self, seat: usize, card: usize) -> Result<(), RuleError> {
+        if card >= self.hands[seat].len() {
+            return Err(RuleError::NoSuchCard);
+        }
+        //nobody plays on a finished trick until it is won
+        if seat != self.turn() || self.trick.len() == self.seats.players() {
+            return Err(RuleError::NotYourTurn);
+        }
+        match rules::illegal_move_reason(
+            &self.hands[seat],
+            card,
+            &self.trick,
+            self.triunfo,
+            self.play_phase(),
+            self.is_partner_winning(seat),
+            &self.rules,
+        ) {
+            Some(reason) => Err(RuleError::IllegalMove(reason)),
+            None => Ok(()),
+        }
+    }
+
+    fn close_cante_window(&mut self) -> Result<(), RuleError> {
+        if matches!(self.phase, GamePhase::CanteWindow { .. }) {
+            self.set_phase(GamePhase::playing(self.play_phase()))?;
+        }
+        Ok(())
+    }
+
+    fn is_partner_winning(&self, seat: usize) -> bool {
+        self.trick.winner(self.triunfo).is_some_and(|winner| {
+            winner != seat && self.seats.side(winner) == self.seats.side(seat)
+        })
+    }
+
+    fn finish_trick(&mut self, events: &mut Events) -> Result<(), RuleError> {
+        let winner = self
+            .trick
+            .winner(self.triunfo)
+            .expect("a finished trick has cards");
+        let points = self.trick.points();
+        self.emit(
+            GameEvent::TrickWon {
+                seat: winner,
+                points,
+            },
+            events,
+        )?;
+        if self.phase == GamePhase::Scoring {
+            self.score_hand(winner, events)
+        } else {
+            self.check_vueltas_winner(events)
+        }
+    }
+
+    fn draw_cards(&mut self, winner: usize) {
+        //the winner of the trick draws first, then the rest in turn order
+        for seat in self.seats.from(winner) {
+            if let Some(card) = self.talon.draw() {
+                self.hands[seat].push(card);
+            }
+        }
+    }
+
+    fn score_hand(
+        &mut self,
+        last_trick_winner: usize,
+        events: &mut Events,
+    ) -> Result<(), RuleError> {
+        //the diez de últimas closes the hand, then its points are added to the game
+        let mut score = self.hand_score.clone();
+        score.finish(self.seats.side(last_trick_winner), &self.rules);
+        let ultimas = score.ultimas;
+        let hand_points = (0..self.seats.sides())
+            .map(|side| score.total(side))
+            .collect();
+        self.emit(
+            GameEvent::HandScored {
+                score,
+                triunfo: self.triunfo,
+            },
+            events,
+        )?;
+        if let Some(side) = scoring::game_winner(&self.points, ultimas, &self.rules) {
+            return self.emit(GameEvent::GameWon { side }, events);
+        }
+        self.emit(GameEvent::Vueltas { hand_points }, events)?;
+        //the deal passes to the next seat every hand
+        let dealer = self.seats.next(self.dealer);
+        let (hands, talon) = deck::deal(
+            self.seats.players(),
+            self.seats.next(dealer),
+            self.cut_deck,
+            &mut self.deal_rng(),
+        );
+        self.emit(
+            GameEvent::Dealt {
+                dealer,
+                hands,
+                talon,
+            },
+            events,
+        )
+    }
+
+    fn check_vueltas_winner(&mut self, events: &mut Events) -> Result<(), RuleError> {
+        //in the vueltas the game ends as soon as someone reaches the winning points
+        if let Some(side) = self.vueltas_winner() {
+            self.emit(
+                GameEvent::HandScored {
+                    score: self.hand_score.clone(),
+                    triunfo: self.triunfo,
+                },
+                events,
+            )?;
+            self.emit(GameEvent::GameWon { side }, events)?;
+        }
+        Ok(())
+    }
+
+    /// The side that reached the winning points during the vueltas, if any
+    fn vueltas_winner(&self) -> Option<usize> {
+        if !self.vueltas {
+            return None;
+        }
+        (0..self.seats.sides()).find(|&side| self.score(side) >= self.rules.winning_points)
+    }
+
+    fn won_last_trick(&self, seat: usize) -> bool {
+        match self.phase {
+            GamePhase::CanteWindow { seat: winner } => {
+                self.seats.side(winner) == self.seats.side(seat)
+            }
+            _ => false,
+        }
+    }
+
+    fn may_sing(&self, seat: usize) -> bool {
+        //depending on the rules after winning a trick, or else before leading
+        if self.rules.cante_requires_trick {
+            self.won_last_trick(seat)
+        } else {
+            self.turn() == seat && self.trick.is_empty()
+        }
+    }
+
+    fn sing(&mut self, seat: usize, events: &mut Events) -> Result<(), RuleError> {
+        let cante = self.cante_to_sing(seat)?;
+        let points = cante.points(self.triunfo);
+        self.emit(
+            GameEvent::CanteSung {
+                seat,
+                cante,
+                points,
+            },
+            events,
+        )?;
+        self.check_vueltas_winner(events)
+    }
+
+    /// The cante `seat` sings now
+    fn cante_to_sing(&self, seat: usize) -> Result<Cante, RuleError> {
+        //only one cante per won trick, the one in the triunfo palo takes precedence
+        if !self.may_sing(seat) {
+            return Err(RuleError::CannotSing {
+                requires_trick: self.rules.cante_requires_trick,
+            });
+        }
+        self.available_cantes(seat)
+            .first()
+            .copied()
+            .ok_or(RuleError::NothingToSing)
+    }
+
+    fn sing_tute(&mut self, seat: usize, events: &mut Events) -> Result<(), RuleError> {
+        //the four Reyes or the four Caballos win the game outright
+        let figure = self.tute_to_sing(seat)?;
+        self.emit(GameEvent::TuteSung { seat, figure }, events)?;
+        let side = self.seats.side(seat);
+        self.emit(GameEvent::GameWon { side }, events)
+    }
+
+    fn tute_to_sing(&self, seat: usize) -> Result<CardsValues, RuleError> {
+        self.tute_figure(seat).ok_or(RuleError::NoTute)
+    }
+
+    fn exchange_siete(&mut self, seat: usize, events: &mut Events) -> Result<(), RuleError> {
+        let index = self.card_to_exchange(seat)?;
+        let card = self.hands[seat][index];
+        let triunfo_card = *self
+            .talon
+            .triunfo_card()
+            .expect("only exchangeable while the triunfo card is face up");
+        self.emit(
+            GameEvent::Exchanged {
+                seat,
+                card,
+                triunfo_card,
+            },
+            events,
+        )
+    }
+
+    /// The index of the card `seat` exchanges for the triunfo card now
+    fn card_to_exchange(&self, seat: usize) -> Result<usize, RuleError> {
+        self.exchangeable_card(seat)
+            .ok_or(RuleError::NothingToExchange)
+    }
+}
+
+fn invalid(reason: String) -> RuleError {
+    RuleError::InvalidEvent(reason)
+}
+
+/// A replayed action that the checks of `apply` would not have let `seat` take
+fn not_allowed(seat: usize, taking: &str, card: &Card, error: RuleError) -> RuleError {
+    invalid(format!(
+        "seat {} could not {} the {} of {}: {}",
+        seat,
+        taking,
+        card.name(),
+        card.palo,
+        error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai;
+
+    fn heuristic_game(players: usize, rules: RuleSet, seed: u64) -> GameState {
+        let mut game = GameState::new(Seats::new(players), rules, 0, true, seed);
+        ai::play_out_heuristic(&mut game, |_, _| true);
+        game
+    }
+
+    #[test]
+    fn same_seed_same_deal() {
+        let game = |seed| GameState::new(Seats::new(4), RuleSet::CLASICO, 3, true, seed);
+        let (first, again, other) = (game(11), game(11), game(12));
+        for seat in 0..4 {
+            assert_eq!(first.hand(seat), again.hand(seat));
+        }
+        assert_eq!(first.talon(), again.talon());
+        assert_ne!(first.talon(), other.talon());
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let first = heuristic_game(2, RuleSet::CON_DOS, 5);
+        let again = heuristic_game(2, RuleSet::CON_DOS, 5);
+        assert_eq!(first.log(), again.log());
+    }
+
+    #[test]
+    fn rejects_seats_that_are_not_at_the_table() {
+        let mut game = GameState::new(Seats::new(2), RuleSet::CLASICO, 0, true, 1);
+        for action in [
+            Action::Play { seat: 3, card: 0 },
+            Action::Sing { seat: 2 },
+            Action::SingTute { seat: 2 },
+            Action::Exchange { seat: 7 },
+        ] {
+            assert_eq!(game.apply(action), Err(RuleError::NoSuchSeat));
+        }
+    }
+
+    #[test]
+    fn replay_rebuilds_the_state() {
+        for players in 2..=4 {
+            for (seed, rules) in RuleSet::PRESETS.into_iter().enumerate() {
+                let game = heuristic_game(players, rules, seed as u64);
+                assert!(game.phase().is_over());
+                //every prefix of the log is a game that was played up to it
+                for len in 1..=game.log().len() {
+                    let replayed = GameState::replay(&game.log()[..len]).unwrap();
+                    assert_eq!(replayed.log(), &game.log()[..len]);
+                }
+                let replayed = GameState::replay(game.log()).unwrap();
+                assert_eq!(format!("{:?}", replayed), format!("{:?}", game));
+            }
+        }
+    }
+
+    #[test]
+    fn replay_needs_the_start() {
+        let game = heuristic_game(2, RuleSet::CLASICO, 1);
+        assert_eq!(
+            GameState::replay(&game.log()[1..]).unwrap_err(),
+            RuleError::NotStarted
+        );
+        let mut log = game.log().to_vec();
+        log.insert(1, log[0].clone());
+        assert_eq!(
+            GameState::replay(&log).unwrap_err(),
+            RuleError::AlreadyStarted
+        );
+    }
+
+    fn assert_invalid(log: &[GameEvent]) {
+        assert!(
+            matches!(GameState::replay(log), Err(RuleError::InvalidEvent(_))),
+            "{:?} was replayed",
+            log.last()
+        );
+    }
+
+    #[test]
+    fn replay_rejects_events_that_could_not_happen() {
+        //a game that goes to the vueltas
+        let game = heuristic_game(2, RuleSet::CLASICO, 3);
+        let rejects = |change: &dyn Fn(&mut GameEvent) -> bool| {
+            let mut log = game.log().to_vec();
+            let changed = log.iter_mut().position(change).expect("an event to change");
+            assert_invalid(&log[..=changed]);
+        };
+        rejects(&|event| match event {
+            GameEvent::TrickWon { seat, .. } => {
+                *seat = 1 - *seat;
+                true
+            }
+            _ => false,
+        });
+        rejects(&|event| match event {
+            GameEvent::TrickWon { points, .. } => {
+                *points += 1;
+                true
+            }
+            _ => false,
+        });
+        rejects(&|event| match event {
+            GameEvent::Dealt { hands, .. } => {
+                hands.swap(0, 1);
+                true
+            }
+            _ => false,
+        });
+        rejects(&|event| match event {
+            GameEvent::HandScored { score, .. } => {
+                score.cantes[0] += 20;
+                true
+            }
+            _ => false,
+        });
+        rejects(&|event| match event {
+            GameEvent::Vueltas { hand_points } => {
+                hand_points[0] += 1;
+                true
+            }
+            _ => false,
+        });
+        rejects(&|event| match event {
+            GameEvent::GameWon { side } => {
+                *side = 1 - *side;
+                true
+            }
+            _ => false,
+        });
+        //nothing happens once the game is over
+        assert_invalid(&[game.log(), &game.log()[1..2]].concat());
+    }
+
+    #[test]
+    fn replay_rejects_actions_apply_would_not_take() {
+        let game = GameState::new(Seats::new(2), RuleSet::CLASICO, 0, true, 3);
+        let after_deal = |events: &[GameEvent]| [game.log(), events].concat();
+        let seat = game.turn();
+        let other = 1 - seat;
+        let triunfo_card = *game.talon().triunfo_card().unwrap();
+        assert_invalid(&after_deal(&[GameEvent::GameWon { side: 1 }]));
+        assert_invalid(&after_deal(&[GameEvent::TuteSung {
+            seat: other,
+            figure: CardsValues::Rey,
+        }]));
+        assert_invalid(&after_deal(&[GameEvent::CardPlayed {
+            seat: other,
+            card: game.hand(other)[0],
+        }]));
+        assert_invalid(&after_deal(&[
+            GameEvent::CardPlayed {
+                seat,
+                card: game.hand(seat)[0],
+            },
+            GameEvent::CardPlayed {
+                seat,
+                card: game.hand(seat)[1],
+            },
+        ]));
+        //the exchange is only after winning a trick, and only of the Siete of triunfo
+        assert_invalid(&after_deal(&[GameEvent::Exchanged {
+            seat,
+            card: game.hand(seat)[0],
+            triunfo_card,
+        }]));
+        let mut played = game.clone();
+        while !matches!(played.phase(), GamePhase::CanteWindow { .. }) {
+            let seat = played.turn();
+            played.apply(Action::Play { seat, card: 0 }).unwrap();
+        }
+        let GamePhase::CanteWindow { seat: winner } = played.phase() else {
+            unreachable!()
+        };
+        let card = (0..played.hand(winner).len())
+            .find(|&index| played.exchangeable_card(winner) != Some(index))
+            .map(|index| played.hand(winner)[index])
+            .unwrap();
+        assert_invalid(
+            &[
+                played.log(),
+                &[GameEvent::Exchanged {
+                    seat: winner,
+                    card,
+                    triunfo_card,
+                }],
+            ]
+            .concat(),
+        );
+    }
+}
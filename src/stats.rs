@@ -0,0 +1,122 @@
+use std::{
+    env, fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use guinotecli::ai::Difficulty;
+
+/// The file the results are kept in, unless `GUINOTECLI_STATS` names another one
+const STATS_FILE: &str = ".guinotecli_stats";
+
+/// The result of a finished game, one line of the stats file
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub difficulty: Difficulty,
+    pub players: usize,
+    pub won: bool,
+    //the final points of each side
+    pub points: Vec<u16>,
+    //the seed of the session and which of its games this was, `--seed` plays the session
+    //again and with the same keys this game is dealt and played the same
+    pub seed: u64,
+    pub game: u32,
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let points = self
+            .points
+            .iter()
+            .map(|points| points.to_string())
+            .collect::<Vec<String>>();
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.difficulty.name(),
+            self.players,
+            if self.won { "won" } else { "lost" },
+            points.join("-"),
+            self.seed,
+            self.game
+        )
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid stats line: {}", line);
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        let [difficulty, players, won, points, seed, game] = fields[..] else {
+            return Err(invalid());
+        };
+        Ok(GameRecord {
+            difficulty: Difficulty::ALL
+                .into_iter()
+                .find(|level| level.name() == difficulty)
+                .ok_or_else(invalid)?,
+            players: players.parse().map_err(|_| invalid())?,
+            won: match won {
+                "won" => true,
+                "lost" => false,
+                _ => return Err(invalid()),
+            },
+            points: points
+                .split('-')
+                .map(|points| points.parse().map_err(|_| invalid()))
+                .collect::<Result<Vec<u16>, String>>()?,
+            seed: seed.parse().map_err(|_| invalid())?,
+            game: game.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Every game played against the computer, kept across sessions in the stats file
+#[derive(Debug, Default)]
+pub struct Stats {
+    //without a home directory nothing is saved, the stats only last for the session
+    path: Option<PathBuf>,
+    records: Vec<GameRecord>,
+}
+
+impl Stats {
+    /// The stats saved so far, lines that can not be read are left out
+    pub fn load() -> Self {
+        let path = env::var_os("GUINOTECLI_STATS")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(STATS_FILE)));
+        let records = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| text.lines().filter_map(|line| line.parse().ok()).collect())
+            .unwrap_or_default();
+        Stats { path, records }
+    }
+
+    /// Adds `record` to the stats and appends it to the stats file
+    pub fn record(&mut self, record: GameRecord) -> io::Result<()> {
+        let line = record.to_string();
+        self.records.push(record);
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// The games won and played against the computer at `difficulty`, whatever the budget of
+    /// the expert, which the stats file does not keep
+    pub fn results(&self, difficulty: Difficulty) -> (usize, usize) {
+        let games = self
+            .records
+            .iter()
+            .filter(|record| record.difficulty.name() == difficulty.name());
+        let played = games.clone().count();
+        let won = games.filter(|record| record.won).count();
+        (won, played)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{Card, Palos};
+
+/// Whether `x`, played after `y`, defeats it. `y` must be the card winning the trick so far,
+/// a card that neither follows its palo nor is a triunfo never wins
+pub fn do_x_defeat_y(x: &Card, y: &Card, triunfo: Palos) -> bool {
+    if x.palo == triunfo && y.palo != triunfo {
+        return true;
+    }
+    x.palo == y.palo && x.kill_power() > y.kill_power()
+}
+
+/// The cards played in a single round, in the order they were played. Seats are numbered
+/// in turn order so any number of players can take part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trick {
+    leader: usize,
+    cards: Vec<(usize, Card)>,
+}
+
+impl Trick {
+    pub fn new(leader: usize) -> Self {
+        Trick {
+            leader,
+            cards: Vec::new(),
+        }
+    }
+
+    pub fn play(&mut self, seat: usize, card: Card) {
+        self.cards.push((seat, card));
+    }
+
+    pub fn leader(&self) -> usize {
+        self.leader
+    }
+
+    pub fn led_card(&self) -> Option<&Card> {
+        self.cards.first().map(|(_, card)| card)
+    }
+
+    pub fn led_palo(&self) -> Option<Palos> {
+        self.led_card().map(|card| card.palo)
+    }
+
+    pub fn cards(&self) -> &[(usize, Card)] {
+        &self.cards
+    }
+
+    pub fn card_of(&self, seat: usize) -> Option<&Card> {
+        self.cards
+            .iter()
+            .find(|(s, _)| *s == seat)
+            .map(|(_, card)| card)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The seat and card winning the trick so far, the highest triunfo or else the highest
+    /// card of the led palo
+    pub fn winning(&self, triunfo: Palos) -> Option<(usize, &Card)> {
+        let mut cards = self.cards.iter();
+        let (seat, card) = cards.next()?;
+        let winning = cards.fold((*seat, card), |(seat, winning), (s, card)| {
+            if do_x_defeat_y(card, winning, triunfo) {
+                (*s, card)
+            } else {
+                (seat, winning)
+            }
+        });
+        Some(winning)
+    }
+
+    pub fn winner(&self, triunfo: Palos) -> Option<usize> {
+        self.winning(triunfo).map(|(seat, _)| seat)
+    }
+
+    /// The points of the cards in the trick
+    pub fn points(&self) -> u16 {
+        self.cards
+            .iter()
+            .map(|(_, card)| u16::from(card.value()))
+            .sum()
+    }
+}